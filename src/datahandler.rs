@@ -1,13 +1,20 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{sync::Arc, time::Duration};
 
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
 
 mod database;
 mod dataworker;
+mod library;
+mod lobby_list;
 
+pub use database::JoinCodeInfo;
 use dataworker::{DataAccess, DataWorker};
+pub use library::{
+	LibraryEdit, LibraryError, LibraryRevision, LibraryRevisionInfo, QuestionLibrary,
+};
+pub use lobby_list::{LobbyFilter, LobbyListEntry, LobbyListPage, LobbyListUpdate, MAX_PAGE_SIZE};
 
-use crate::game::Gameshow;
+use crate::game::{Gameshow, Question, QuestionSetInfo, QuestionSetMeta};
 
 /// Thread-safe handler to access the database from multiple instances
 #[derive(Clone)]
@@ -89,10 +96,125 @@ impl DataHandler {
 			.map_err(|_err| "db receive data access result error (dropped channel)")
 	}
 
+	/// Get the UUID of a lobby by its UUID or join code (if valid, else None)
+	pub async fn get_lobby_id(&self, uuid: String) -> Result<Option<String>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::GetLobbyId(result_sender, uuid))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Get the current join code of a lobby (by lobby UUID)
+	pub async fn get_join_code(&self, lobby: String) -> Result<Option<JoinCodeInfo>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::GetJoinCode(result_sender, lobby))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Replace the join code of a lobby (by lobby UUID), optionally expiring
+	pub async fn regenerate_join_code(
+		&self,
+		lobby: String,
+		valid_for: Option<Duration>,
+	) -> Result<Option<JoinCodeInfo>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::RegenerateJoinCode(result_sender, lobby, valid_for))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Get the lobbies a player administrates or has joined
+	pub async fn get_player_lobbies(&self, uuid: &str) -> Result<Vec<Arc<Gameshow>>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::GetLobbies(result_sender))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		let lobbies = result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")?;
+
+		let mut player_lobbies = Vec::new();
+		for lobby in lobbies {
+			if lobby.get_admin_uuid().await == uuid || lobby.is_joined(uuid).await {
+				player_lobbies.push(lobby);
+			}
+		}
+		Ok(player_lobbies)
+	}
+
+	/// Get a page of the listed and open lobbies matching the filter
+	pub async fn list_lobbies(
+		&self,
+		filter: &LobbyFilter,
+		page: usize,
+		page_size: usize,
+	) -> Result<LobbyListPage, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::GetListedLobbies(result_sender))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		let lobbies = result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")?;
+		let question_sets = self.get_question_sets().await?;
+
+		let mut entries = Vec::new();
+		for (lobby_id, lobby) in lobbies {
+			let listing = lobby.get_listing().await;
+			if let Some(entry) = lobby_list::make_entry(lobby_id, listing, &question_sets) {
+				if filter.matches(&entry) {
+					entries.push(entry);
+				}
+			}
+		}
+		Ok(lobby_list::make_page(entries, page, page_size))
+	}
+
+	/// Get the lobby list entry of a lobby (None unless it is listed and open)
+	pub async fn get_lobby_list_entry(
+		&self,
+		lobby_id: String,
+	) -> Result<Option<LobbyListEntry>, &'static str> {
+		let lobby = match self.get_lobby(lobby_id.clone()).await? {
+			Some(lobby) if lobby.is_listed() => lobby,
+			_ => return Ok(None),
+		};
+		let question_sets = self.get_question_sets().await?;
+		let listing = lobby.get_listing().await;
+		Ok(lobby_list::make_entry(lobby_id, listing, &question_sets))
+	}
+
+	/// Subscribe to the UUIDs of listed lobbies that changed
+	pub async fn subscribe_lobby_list(&self) -> Result<broadcast::Receiver<String>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::SubscribeLobbyList(result_sender))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
 	/// Set the available question sets (server side files)
 	pub async fn set_question_sets(
 		&self,
-		question_sets: Vec<(String, PathBuf)>,
+		question_sets: Vec<QuestionSetInfo>,
 	) -> Result<(), &'static str> {
 		let (result_sender, result_receiver) = oneshot::channel();
 		self.sender
@@ -105,7 +227,7 @@ impl DataHandler {
 	}
 
 	/// Get the available question sets (server side files)
-	pub async fn get_question_sets(&self) -> Result<Vec<(String, PathBuf)>, &'static str> {
+	pub async fn get_question_sets(&self) -> Result<Vec<QuestionSetInfo>, &'static str> {
 		let (result_sender, result_receiver) = oneshot::channel();
 		self.sender
 			.send(DataAccess::GetQuestionSets(result_sender))
@@ -115,6 +237,126 @@ impl DataHandler {
 			.await
 			.map_err(|_err| "db receive data access result error (dropped channel)")
 	}
+
+	/// Set the question library (server side sets with revisions)
+	pub async fn set_question_library(&self, library: QuestionLibrary) -> Result<(), &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::SetQuestionLibrary(result_sender, library))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Get the latest revisions of all library question sets
+	pub async fn get_library_sets(&self) -> Result<Vec<QuestionSetInfo>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::GetLibrarySets(result_sender))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Get a revision of a library question set (the latest if None)
+	pub async fn get_library_revision(
+		&self,
+		name: String,
+		revision: Option<usize>,
+	) -> Result<Option<LibraryRevision>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::GetLibraryRevision(result_sender, name, revision))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Get the revision history of a library question set
+	pub async fn get_library_revisions(
+		&self,
+		name: String,
+	) -> Result<Option<Vec<LibraryRevisionInfo>>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::GetLibraryRevisions(result_sender, name))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Create a new library question set
+	pub async fn create_library_set(
+		&self,
+		name: String,
+		meta: QuestionSetMeta,
+		questions: Vec<Question>,
+	) -> Result<Result<LibraryRevision, LibraryError>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::CreateLibrarySet(result_sender, name, meta, questions))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Copy a revision of a library question set to a new set
+	pub async fn duplicate_library_set(
+		&self,
+		name: String,
+		revision: Option<usize>,
+		new_name: String,
+	) -> Result<Result<LibraryRevision, LibraryError>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::DuplicateLibrarySet(result_sender, name, revision, new_name))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Change a library question set, creating a new revision
+	pub async fn edit_library_set(
+		&self,
+		name: String,
+		edit: LibraryEdit,
+	) -> Result<Result<LibraryRevision, LibraryError>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::EditLibrarySet(result_sender, name, edit))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
+
+	/// Delete a library question set with all revisions
+	pub async fn delete_library_set(
+		&self,
+		name: String,
+	) -> Result<Result<(), LibraryError>, &'static str> {
+		let (result_sender, result_receiver) = oneshot::channel();
+		self.sender
+			.send(DataAccess::DeleteLibrarySet(result_sender, name))
+			.await
+			.map_err(|_err| "db send data access error (dropped channel)")?;
+		result_receiver
+			.await
+			.map_err(|_err| "db receive data access result error (dropped channel)")
+	}
 }
 
 impl Default for DataHandler {
@@ -1,18 +1,46 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+	collections::HashMap,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
-use crate::game::Gameshow;
+use super::library::{
+	LibraryEdit, LibraryError, LibraryRevision, LibraryRevisionInfo, LibraryWrite, QuestionLibrary,
+};
+use crate::game::{Gameshow, Question, QuestionSetInfo, QuestionSetMeta};
+
+//short codes to join lobbies with, without ambiguous characters like 0/O and
+// 1/I
+const JOIN_CODE_CHARACTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const JOIN_CODE_LENGTH: usize = 6;
+const LOBBY_LIST_CAPACITY: usize = 100; //lobby list updates buffered for slow subscribers
 
 pub struct DataBase {
 	players: HashMap<String, String>,
 	lobbies: HashMap<String, Arc<Gameshow>>,
-	question_sets: Vec<(String, PathBuf)>,
+	join_codes: HashMap<String, JoinCode>, //secondary index of the lobbies by join code
+	lobby_join_codes: HashMap<String, String>, //join code of each lobby
+	lobby_list_sender: broadcast::Sender<String>, //UUIDs of listed lobbies that changed
+	question_sets: Vec<QuestionSetInfo>,
+	library: QuestionLibrary,
 }
 
 impl DataBase {
 	pub fn new() -> Self {
-		DataBase { players: HashMap::new(), lobbies: HashMap::new(), question_sets: Vec::new() }
+		DataBase {
+			players: HashMap::new(),
+			lobbies: HashMap::new(),
+			join_codes: HashMap::new(),
+			lobby_join_codes: HashMap::new(),
+			lobby_list_sender: broadcast::channel(LOBBY_LIST_CAPACITY).0,
+			question_sets: Vec::new(),
+			library: QuestionLibrary::new(),
+		}
 	}
 
 	pub fn create_player(&mut self, name: String) -> String {
@@ -50,24 +78,262 @@ impl DataBase {
 		}
 
 		//add lobby
-		self.lobbies.insert(uuid.clone(), Arc::new(Gameshow::new(admin_uuid, admin_name)));
+		let mut lobby = Gameshow::new(admin_uuid, admin_name);
+		lobby.set_listing_updates(uuid.clone(), self.lobby_list_sender.clone());
+		self.lobbies.insert(uuid.clone(), Arc::new(lobby));
+		self.regenerate_join_code(uuid.clone(), None);
 		//return UUID
 		uuid
 	}
 
-	pub fn get_lobby(&self, uuid: String) -> Option<Arc<Gameshow>> {
-		if !self.lobbies.contains_key(&uuid) {
-			None
-		} else {
-			Some(self.lobbies[&uuid].clone())
+	//lobbies are found by their UUID or their join code
+	pub fn get_lobby(&mut self, uuid: String) -> Option<Arc<Gameshow>> {
+		let uuid = self.get_lobby_id(uuid)?;
+		Some(self.lobbies[&uuid].clone())
+	}
+
+	//expired join codes are removed when they are looked up
+	pub fn get_lobby_id(&mut self, uuid: String) -> Option<String> {
+		if self.lobbies.contains_key(&uuid) {
+			return Some(uuid);
+		}
+		let code = uuid.to_uppercase();
+		let join_code = self.join_codes.get(&code)?;
+		if !join_code.is_valid() {
+			self.remove_join_code(&code);
+			return None;
+		}
+		Some(join_code.lobby.clone()).filter(|lobby| self.lobbies.contains_key(lobby))
+	}
+
+	pub fn get_join_code(&mut self, lobby: String) -> Option<JoinCodeInfo> {
+		let code = self.lobby_join_codes.get(&lobby)?.clone();
+		let join_code = self.join_codes.get(&code)?;
+		if !join_code.is_valid() {
+			self.remove_join_code(&code);
+			return None;
 		}
+		Some(join_code.info(&code))
 	}
 
-	pub fn set_question_sets(&mut self, question_sets: Vec<(String, PathBuf)>) {
+	fn remove_join_code(&mut self, code: &str) {
+		if let Some(join_code) = self.join_codes.remove(code) {
+			self.lobby_join_codes.remove(&join_code.lobby);
+		}
+	}
+
+	//replace the lobby's join code (and remove expired ones)
+	pub fn regenerate_join_code(
+		&mut self,
+		lobby: String,
+		valid_for: Option<Duration>,
+	) -> Option<JoinCodeInfo> {
+		if !self.lobbies.contains_key(&lobby) {
+			return None;
+		}
+		if let Some(code) = self.lobby_join_codes.get(&lobby).cloned() {
+			self.remove_join_code(&code);
+		}
+		let expired: Vec<String> = self
+			.join_codes
+			.iter()
+			.filter(|(_, join_code)| !join_code.is_valid())
+			.map(|(code, _)| code.clone())
+			.collect();
+		for code in expired {
+			self.remove_join_code(&code);
+		}
+
+		//be sure the code is REALLY unique
+		let mut rng = rand::thread_rng();
+		let mut code = String::new();
+		while code.is_empty() || self.join_codes.contains_key(&code) {
+			code = (0..JOIN_CODE_LENGTH)
+				.filter_map(|_| JOIN_CODE_CHARACTERS.choose(&mut rng).map(|c| char::from(*c)))
+				.collect();
+		}
+
+		//a validity too long to represent never expires
+		let expires = valid_for.and_then(|valid_for| Instant::now().checked_add(valid_for));
+		let join_code = JoinCode { lobby: lobby.clone(), expires };
+		let info = join_code.info(&code);
+		self.join_codes.insert(code.clone(), join_code);
+		self.lobby_join_codes.insert(lobby, code);
+		Some(info)
+	}
+
+	pub fn get_lobbies(&self) -> Vec<Arc<Gameshow>> {
+		self.lobbies.values().cloned().collect()
+	}
+
+	pub fn get_listed_lobbies(&self) -> Vec<(String, Arc<Gameshow>)> {
+		self.lobbies
+			.iter()
+			.filter(|(_, lobby)| lobby.is_listed())
+			.map(|(uuid, lobby)| (uuid.clone(), lobby.clone()))
+			.collect()
+	}
+
+	pub fn subscribe_lobby_list(&self) -> broadcast::Receiver<String> {
+		self.lobby_list_sender.subscribe()
+	}
+
+	pub fn set_question_sets(&mut self, question_sets: Vec<QuestionSetInfo>) {
 		self.question_sets = question_sets;
 	}
 
-	pub fn get_question_sets(&self) -> Vec<(String, PathBuf)> {
-		self.question_sets.clone()
+	//file based question sets and the latest revisions of library sets
+	pub fn get_question_sets(&self) -> Vec<QuestionSetInfo> {
+		let mut question_sets = self.question_sets.clone();
+		question_sets.extend(self.library.list());
+		question_sets.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+		question_sets
+	}
+
+	pub fn set_question_library(&mut self, library: QuestionLibrary) {
+		self.library = library;
+	}
+
+	pub fn get_library_sets(&self) -> Vec<QuestionSetInfo> {
+		self.library.list()
+	}
+
+	pub fn get_library_revision(
+		&self,
+		name: String,
+		revision: Option<usize>,
+	) -> Option<LibraryRevision> {
+		self.library.get(&name, revision)
+	}
+
+	pub fn get_library_revisions(&self, name: String) -> Option<Vec<LibraryRevisionInfo>> {
+		self.library.revisions(&name)
+	}
+
+	pub fn create_library_set(
+		&mut self,
+		name: String,
+		meta: QuestionSetMeta,
+		questions: Vec<Question>,
+	) -> Result<(LibraryRevision, LibraryWrite), LibraryError> {
+		//names must be unique among file based sets too
+		if self.question_sets.iter().any(|set| set.name == name) {
+			return Err(LibraryError::AlreadyExists);
+		}
+		self.library.create(name, meta, questions)
+	}
+
+	pub fn duplicate_library_set(
+		&mut self,
+		name: String,
+		revision: Option<usize>,
+		new_name: String,
+	) -> Result<(LibraryRevision, LibraryWrite), LibraryError> {
+		if self.question_sets.iter().any(|set| set.name == new_name) {
+			return Err(LibraryError::AlreadyExists);
+		}
+		self.library.duplicate(&name, revision, new_name)
+	}
+
+	pub fn edit_library_set(
+		&mut self,
+		name: String,
+		edit: LibraryEdit,
+	) -> Result<(LibraryRevision, LibraryWrite), LibraryError> {
+		self.library.edit(&name, edit)
+	}
+
+	pub fn delete_library_set(&mut self, name: String) -> Result<((), LibraryWrite), LibraryError> {
+		self.library.delete(&name)
+	}
+}
+
+//join code of a lobby, for invites that optionally expire
+struct JoinCode {
+	lobby: String,
+	expires: Option<Instant>,
+}
+
+//join code as shown to players
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JoinCodeInfo {
+	pub join_code: String,
+	pub expires_in: Option<u64>, //seconds until the code expires, never if none
+}
+
+impl JoinCode {
+	fn is_valid(&self) -> bool {
+		self.expires.is_none_or(|expires| expires > Instant::now())
+	}
+
+	fn info(&self, code: &str) -> JoinCodeInfo {
+		JoinCodeInfo {
+			join_code: code.to_owned(),
+			expires_in: self
+				.expires
+				.map(|expires| expires.saturating_duration_since(Instant::now()).as_secs()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn database_with_lobby() -> (DataBase, String) {
+		let mut db = DataBase::new();
+		let admin = db.create_player(String::from("Admin"));
+		let lobby = db.create_lobby(admin, String::from("Admin"));
+		(db, lobby)
+	}
+
+	#[test]
+	fn join_codes_are_generated() {
+		let (mut db, lobby) = database_with_lobby();
+		let info = db.get_join_code(lobby.clone()).unwrap();
+		assert_eq!(info.join_code.len(), JOIN_CODE_LENGTH);
+		assert!(info.join_code.bytes().all(|c| JOIN_CODE_CHARACTERS.contains(&c)));
+		assert!(info.expires_in.is_none());
+
+		//codes are found case insensitively, just like the lobby UUID
+		assert_eq!(db.get_lobby_id(info.join_code.to_lowercase()), Some(lobby.clone()));
+		assert_eq!(db.get_lobby_id(lobby.clone()), Some(lobby.clone()));
+		assert!(db.get_lobby_id(String::from("UNKNOWN")).is_none());
+		assert!(db.get_join_code(String::from("unknown")).is_none());
+		assert!(db.regenerate_join_code(String::from("unknown"), None).is_none());
+	}
+
+	#[test]
+	fn regenerated_codes_replace_old_ones() {
+		let (mut db, lobby) = database_with_lobby();
+		let old = db.get_join_code(lobby.clone()).unwrap().join_code;
+		let new = db.regenerate_join_code(lobby.clone(), None).unwrap().join_code;
+		assert_ne!(old, new);
+		assert!(db.get_lobby_id(old).is_none());
+		assert_eq!(db.get_lobby_id(new.clone()), Some(lobby.clone()));
+		assert_eq!(db.get_join_code(lobby).unwrap().join_code, new);
+		assert_eq!(db.join_codes.len(), 1);
+	}
+
+	#[test]
+	fn expired_codes_are_removed() {
+		let (mut db, lobby) = database_with_lobby();
+		let info = db.regenerate_join_code(lobby.clone(), Some(Duration::from_secs(60))).unwrap();
+		assert!(matches!(info.expires_in, Some(59..=60)));
+		assert!(db.get_join_code(lobby.clone()).is_some());
+
+		//on lookup by code
+		let code = db.regenerate_join_code(lobby.clone(), Some(Duration::ZERO)).unwrap().join_code;
+		assert!(db.get_lobby_id(code).is_none());
+		assert!(db.join_codes.is_empty() && db.lobby_join_codes.is_empty());
+
+		//on lookup by lobby
+		db.regenerate_join_code(lobby.clone(), Some(Duration::ZERO));
+		assert!(db.get_join_code(lobby.clone()).is_none());
+		assert!(db.join_codes.is_empty() && db.lobby_join_codes.is_empty());
+
+		//validities too long to represent never expire
+		let info = db.regenerate_join_code(lobby, Some(Duration::MAX)).unwrap();
+		assert!(info.expires_in.is_none());
 	}
 }
@@ -1,13 +1,19 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{sync::Arc, time::Duration};
 
 use actix_web::rt;
 use tokio::{
-	sync::{mpsc, oneshot},
+	sync::{broadcast, mpsc, oneshot},
 	task::JoinHandle,
 };
 
-use super::database::DataBase;
-use crate::game::Gameshow;
+use super::{
+	database::{DataBase, JoinCodeInfo},
+	library::{
+		LibraryEdit, LibraryError, LibraryRevision, LibraryRevisionInfo, LibraryWriter,
+		QuestionLibrary,
+	},
+};
+use crate::game::{Gameshow, Question, QuestionSetInfo, QuestionSetMeta};
 
 const DATA_ACCESS_CAPACITY: usize = 50;
 
@@ -18,8 +24,32 @@ pub enum DataAccess {
 	GetPlayerName(oneshot::Sender<Option<String>>, String),
 	CreateLobby(oneshot::Sender<String>, String, String),
 	GetLobby(oneshot::Sender<Option<Arc<Gameshow>>>, String),
-	SetQuestionSets(oneshot::Sender<()>, Vec<(String, PathBuf)>),
-	GetQuestionSets(oneshot::Sender<Vec<(String, PathBuf)>>),
+	GetLobbyId(oneshot::Sender<Option<String>>, String),
+	GetJoinCode(oneshot::Sender<Option<JoinCodeInfo>>, String),
+	RegenerateJoinCode(oneshot::Sender<Option<JoinCodeInfo>>, String, Option<Duration>),
+	GetLobbies(oneshot::Sender<Vec<Arc<Gameshow>>>),
+	GetListedLobbies(oneshot::Sender<Vec<(String, Arc<Gameshow>)>>),
+	SubscribeLobbyList(oneshot::Sender<broadcast::Receiver<String>>),
+	SetQuestionSets(oneshot::Sender<()>, Vec<QuestionSetInfo>),
+	GetQuestionSets(oneshot::Sender<Vec<QuestionSetInfo>>),
+	SetQuestionLibrary(oneshot::Sender<()>, QuestionLibrary),
+	GetLibrarySets(oneshot::Sender<Vec<QuestionSetInfo>>),
+	GetLibraryRevision(oneshot::Sender<Option<LibraryRevision>>, String, Option<usize>),
+	GetLibraryRevisions(oneshot::Sender<Option<Vec<LibraryRevisionInfo>>>, String),
+	CreateLibrarySet(
+		oneshot::Sender<Result<LibraryRevision, LibraryError>>,
+		String,
+		QuestionSetMeta,
+		Vec<Question>,
+	),
+	DuplicateLibrarySet(
+		oneshot::Sender<Result<LibraryRevision, LibraryError>>,
+		String,
+		Option<usize>,
+		String,
+	),
+	EditLibrarySet(oneshot::Sender<Result<LibraryRevision, LibraryError>>, String, LibraryEdit),
+	DeleteLibrarySet(oneshot::Sender<Result<(), LibraryError>>, String),
 }
 
 /// Single instance of worker to access the database
@@ -42,6 +72,7 @@ impl DataWorker {
 
 	/// handle received DataAccesses
 	async fn handler(mut self) {
+		let library_writer = LibraryWriter::spawn();
 		while let Some(access) = self.receiver.recv().await {
 			match access {
 				DataAccess::CreatePlayer(result_sender, name) => {
@@ -64,6 +95,30 @@ impl DataWorker {
 					let result = self.db.get_lobby(uuid);
 					result_sender.send(result).ok();
 				}
+				DataAccess::GetLobbyId(result_sender, uuid) => {
+					let result = self.db.get_lobby_id(uuid);
+					result_sender.send(result).ok();
+				}
+				DataAccess::GetJoinCode(result_sender, lobby) => {
+					let result = self.db.get_join_code(lobby);
+					result_sender.send(result).ok();
+				}
+				DataAccess::RegenerateJoinCode(result_sender, lobby, valid_for) => {
+					let result = self.db.regenerate_join_code(lobby, valid_for);
+					result_sender.send(result).ok();
+				}
+				DataAccess::GetLobbies(result_sender) => {
+					let result = self.db.get_lobbies();
+					result_sender.send(result).ok();
+				}
+				DataAccess::GetListedLobbies(result_sender) => {
+					let result = self.db.get_listed_lobbies();
+					result_sender.send(result).ok();
+				}
+				DataAccess::SubscribeLobbyList(result_sender) => {
+					let result = self.db.subscribe_lobby_list();
+					result_sender.send(result).ok();
+				}
 				DataAccess::SetQuestionSets(result_sender, question_sets) => {
 					self.db.set_question_sets(question_sets);
 					result_sender.send(()).ok();
@@ -72,6 +127,38 @@ impl DataWorker {
 					let result = self.db.get_question_sets();
 					result_sender.send(result).ok();
 				}
+				DataAccess::SetQuestionLibrary(result_sender, library) => {
+					self.db.set_question_library(library);
+					result_sender.send(()).ok();
+				}
+				DataAccess::GetLibrarySets(result_sender) => {
+					let result = self.db.get_library_sets();
+					result_sender.send(result).ok();
+				}
+				DataAccess::GetLibraryRevision(result_sender, name, revision) => {
+					let result = self.db.get_library_revision(name, revision);
+					result_sender.send(result).ok();
+				}
+				DataAccess::GetLibraryRevisions(result_sender, name) => {
+					let result = self.db.get_library_revisions(name);
+					result_sender.send(result).ok();
+				}
+				DataAccess::CreateLibrarySet(result_sender, name, meta, questions) => {
+					let result = self.db.create_library_set(name, meta, questions);
+					library_writer.persist(result, result_sender);
+				}
+				DataAccess::DuplicateLibrarySet(result_sender, name, revision, new_name) => {
+					let result = self.db.duplicate_library_set(name, revision, new_name);
+					library_writer.persist(result, result_sender);
+				}
+				DataAccess::EditLibrarySet(result_sender, name, edit) => {
+					let result = self.db.edit_library_set(name, edit);
+					library_writer.persist(result, result_sender);
+				}
+				DataAccess::DeleteLibrarySet(result_sender, name) => {
+					let result = self.db.delete_library_set(name);
+					library_writer.persist(result, result_sender);
+				}
 			}
 		}
 	}
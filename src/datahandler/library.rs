@@ -0,0 +1,435 @@
+use std::{
+	collections::HashMap,
+	fmt, fs, io,
+	path::PathBuf,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use actix_web::rt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::game::{self, Question, QuestionSetInfo, QuestionSetMeta};
+
+const LIBRARY_FOLDER: &str = "./questions/library";
+
+/// Errors of question library operations
+#[derive(Debug)]
+pub enum LibraryError {
+	NotFound,
+	AlreadyExists,
+	InvalidName,
+	InvalidIndex,
+	Io(io::Error),
+}
+
+impl fmt::Display for LibraryError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			LibraryError::NotFound => write!(f, "Question set or revision not found!"),
+			LibraryError::AlreadyExists => write!(f, "Question set name is already in use!"),
+			LibraryError::InvalidName => {
+				write!(f, "Invalid name: Only letters, digits, '-' and '_' are allowed!")
+			}
+			LibraryError::InvalidIndex => write!(f, "Question index is out of range!"),
+			LibraryError::Io(err) => write!(f, "Question library storage error: {}", err),
+		}
+	}
+}
+
+impl std::error::Error for LibraryError {}
+
+impl From<io::Error> for LibraryError {
+	fn from(err: io::Error) -> Self {
+		LibraryError::Io(err)
+	}
+}
+
+/// One saved state of a library question set
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LibraryRevision {
+	pub revision: usize,
+	pub created: u64, //unix timestamp in seconds
+	pub meta: QuestionSetMeta,
+	pub questions: Vec<Question>,
+}
+
+/// Overview of a revision without its questions
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LibraryRevisionInfo {
+	pub revision: usize,
+	pub created: u64,
+	pub num_questions: usize,
+}
+
+/// Change to apply to the latest revision of a library question set
+pub enum LibraryEdit {
+	ReplaceAll(QuestionSetMeta, Vec<Question>),
+	AddQuestion(Option<usize>, Question),
+	UpdateQuestion(usize, Question),
+	DeleteQuestion(usize),
+	DuplicateQuestion(usize),
+}
+
+/// File change resulting from a library change, to be done by the LibraryWriter
+pub enum LibraryWrite {
+	Save(PathBuf, String),
+	Remove(PathBuf),
+}
+
+impl LibraryWrite {
+	async fn apply(self) -> io::Result<()> {
+		match self {
+			LibraryWrite::Save(path, json_string) => {
+				if let Some(folder) = path.parent() {
+					tokio::fs::create_dir_all(folder).await?;
+				}
+				tokio::fs::write(path, json_string).await
+			}
+			LibraryWrite::Remove(path) => tokio::fs::remove_file(path).await,
+		}
+	}
+}
+
+/// Background task writing library files one after another, so the data worker
+/// does not wait for the file system and writes to one file keep their order
+pub struct LibraryWriter {
+	sender: mpsc::UnboundedSender<(LibraryWrite, oneshot::Sender<io::Result<()>>)>,
+}
+
+impl LibraryWriter {
+	/// Spawn the writer task, it terminates when the LibraryWriter is dropped
+	pub fn spawn() -> Self {
+		let (sender, mut receiver) =
+			mpsc::unbounded_channel::<(LibraryWrite, oneshot::Sender<io::Result<()>>)>();
+		rt::spawn(async move {
+			while let Some((write, result_sender)) = receiver.recv().await {
+				result_sender.send(write.apply().await).ok();
+			}
+		});
+		LibraryWriter { sender }
+	}
+
+	/// Write the file change of a library change and send the result once it is
+	/// stored. The in-memory library is already changed then; after a failed
+	/// write the next change of the set stores its whole history again.
+	pub fn persist<T: Send + 'static>(
+		&self,
+		change: Result<(T, LibraryWrite), LibraryError>,
+		result_sender: oneshot::Sender<Result<T, LibraryError>>,
+	) {
+		let (value, write) = match change {
+			Ok(change) => change,
+			Err(err) => {
+				result_sender.send(Err(err)).ok();
+				return;
+			}
+		};
+		let (write_sender, write_receiver) = oneshot::channel();
+		self.sender.send((write, write_sender)).ok();
+		rt::spawn(async move {
+			let result = match write_receiver.await {
+				Ok(Ok(())) => Ok(value),
+				Ok(Err(err)) => Err(LibraryError::Io(err)),
+				Err(_) => Err(LibraryError::Io(io::Error::other("Library writer stopped!"))),
+			};
+			result_sender.send(result).ok();
+		});
+	}
+}
+
+/// Question sets managed via the API, every change creates a new revision.
+/// Each set is stored as one file containing all of its revisions.
+pub struct QuestionLibrary {
+	folder: PathBuf,
+	sets: HashMap<String, Vec<LibraryRevision>>,
+}
+
+impl QuestionLibrary {
+	/// Create an empty library
+	pub fn new() -> Self {
+		QuestionLibrary { folder: PathBuf::from(LIBRARY_FOLDER), sets: HashMap::new() }
+	}
+
+	/// Load all library question sets from the library folder. Invalid files
+	/// are an error, as saving a set of the same name would overwrite them.
+	pub fn load() -> io::Result<Self> {
+		let folder = PathBuf::from(LIBRARY_FOLDER);
+		fs::create_dir_all(&folder)?;
+
+		let mut sets = HashMap::new();
+		for entry in fs::read_dir(&folder)? {
+			let path = entry?.path();
+			if path.is_dir() || path.extension().is_none_or(|ext| ext != "json") {
+				continue;
+			}
+			let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+			let json_string = fs::read_to_string(&path)?;
+			let revisions: Vec<LibraryRevision> =
+				serde_json::from_str(&json_string).map_err(|err| {
+					io::Error::new(
+						io::ErrorKind::InvalidData,
+						format!("Invalid library question set {}: {}", path.display(), err),
+					)
+				})?;
+			if revisions.is_empty() {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidData,
+					format!("Library question set {} has no revisions", path.display()),
+				));
+			}
+			sets.insert(name, revisions);
+		}
+
+		Ok(QuestionLibrary { folder, sets })
+	}
+
+	/// List the latest revisions of all sets
+	pub fn list(&self) -> Vec<QuestionSetInfo> {
+		let mut list: Vec<QuestionSetInfo> = self
+			.sets
+			.iter()
+			.filter_map(|(name, revisions)| {
+				let latest = revisions.last()?;
+				Some(
+					QuestionSetInfo::new(
+						name.clone(),
+						PathBuf::new(),
+						latest.meta.clone(),
+						&latest.questions,
+					)
+					.with_revision(latest.revision),
+				)
+			})
+			.collect();
+		list.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+		list
+	}
+
+	/// Get a revision of a set, the latest if none is given
+	pub fn get(&self, name: &str, revision: Option<usize>) -> Option<LibraryRevision> {
+		let revisions = self.sets.get(name)?;
+		match revision {
+			Some(revision) => revisions.iter().find(|r| r.revision == revision).cloned(),
+			None => revisions.last().cloned(),
+		}
+	}
+
+	/// List all revisions of a set
+	pub fn revisions(&self, name: &str) -> Option<Vec<LibraryRevisionInfo>> {
+		let revisions = self.sets.get(name)?;
+		Some(
+			revisions
+				.iter()
+				.map(|r| LibraryRevisionInfo {
+					revision: r.revision,
+					created: r.created,
+					num_questions: r.questions.len(),
+				})
+				.collect(),
+		)
+	}
+
+	/// Create a new set, the name must be unused
+	pub fn create(
+		&mut self,
+		name: String,
+		meta: QuestionSetMeta,
+		questions: Vec<Question>,
+	) -> Result<(LibraryRevision, LibraryWrite), LibraryError> {
+		if !game::is_valid_question_set_name(&name) {
+			return Err(LibraryError::InvalidName);
+		}
+		if self.sets.contains_key(&name) {
+			return Err(LibraryError::AlreadyExists);
+		}
+
+		let revision = LibraryRevision { revision: 1, created: now(), meta, questions };
+		let revisions = vec![revision.clone()];
+		let write = self.save(&name, &revisions)?;
+		self.sets.insert(name, revisions);
+		Ok((revision, write))
+	}
+
+	/// Copy a revision of a set to a new set
+	pub fn duplicate(
+		&mut self,
+		name: &str,
+		revision: Option<usize>,
+		new_name: String,
+	) -> Result<(LibraryRevision, LibraryWrite), LibraryError> {
+		let source = self.get(name, revision).ok_or(LibraryError::NotFound)?;
+		self.create(new_name, source.meta, source.questions)
+	}
+
+	/// Apply a change to the latest revision, saving the result as new revision
+	pub fn edit(
+		&mut self,
+		name: &str,
+		edit: LibraryEdit,
+	) -> Result<(LibraryRevision, LibraryWrite), LibraryError> {
+		let latest = self.get(name, None).ok_or(LibraryError::NotFound)?;
+		let mut meta = latest.meta;
+		let mut questions = latest.questions;
+		match edit {
+			LibraryEdit::ReplaceAll(new_meta, new_questions) => {
+				meta = new_meta;
+				questions = new_questions;
+			}
+			LibraryEdit::AddQuestion(position, question) => {
+				let position = position.unwrap_or(questions.len());
+				if position > questions.len() {
+					return Err(LibraryError::InvalidIndex);
+				}
+				questions.insert(position, question);
+			}
+			LibraryEdit::UpdateQuestion(index, question) => {
+				*questions.get_mut(index).ok_or(LibraryError::InvalidIndex)? = question;
+			}
+			LibraryEdit::DeleteQuestion(index) => {
+				if index >= questions.len() {
+					return Err(LibraryError::InvalidIndex);
+				}
+				questions.remove(index);
+			}
+			LibraryEdit::DuplicateQuestion(index) => {
+				let question = questions.get(index).ok_or(LibraryError::InvalidIndex)?.clone();
+				questions.insert(index + 1, question);
+			}
+		}
+
+		let revision =
+			LibraryRevision { revision: latest.revision + 1, created: now(), meta, questions };
+		let mut revisions = self.sets[name].clone();
+		revisions.push(revision.clone());
+		let write = self.save(name, &revisions)?;
+		self.sets.insert(name.to_owned(), revisions);
+		Ok((revision, write))
+	}
+
+	/// Delete a set with all of its revisions
+	pub fn delete(&mut self, name: &str) -> Result<((), LibraryWrite), LibraryError> {
+		if self.sets.remove(name).is_none() {
+			return Err(LibraryError::NotFound);
+		}
+		Ok(((), LibraryWrite::Remove(self.path(name))))
+	}
+
+	fn path(&self, name: &str) -> PathBuf {
+		self.folder.join(format!("{}.json", name))
+	}
+
+	fn save(&self, name: &str, revisions: &[LibraryRevision]) -> io::Result<LibraryWrite> {
+		let json_string = serde_json::to_string_pretty(revisions)?;
+		Ok(LibraryWrite::Save(self.path(name), json_string))
+	}
+}
+
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl Default for QuestionLibrary {
+	fn default() -> Self {
+		QuestionLibrary::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::game::{LocalizedText, QuestionType};
+
+	fn question(text: &str) -> Question {
+		Question {
+			question_type: QuestionType::NormalQuestion,
+			category: LocalizedText::from(String::from("Category")),
+			question: LocalizedText::from(String::from(text)),
+			answers: Vec::new(),
+			correct_answer: 1,
+			hint: None,
+			value: None,
+			host_judges: false,
+		}
+	}
+
+	fn texts(revision: &LibraryRevision) -> Vec<String> {
+		revision.questions.iter().map(|q| q.question("en", "en")).collect()
+	}
+
+	fn library_with_set() -> QuestionLibrary {
+		let mut library = QuestionLibrary::new();
+		let questions = vec![question("a"), question("b")];
+		library.create(String::from("quiz"), QuestionSetMeta::default(), questions).unwrap();
+		library
+	}
+
+	#[test]
+	fn create_checks_names() {
+		let mut library = library_with_set();
+		let result = library.create(String::from("quiz"), QuestionSetMeta::default(), Vec::new());
+		assert!(matches!(result, Err(LibraryError::AlreadyExists)));
+		let result = library.create(String::from("../x"), QuestionSetMeta::default(), Vec::new());
+		assert!(matches!(result, Err(LibraryError::InvalidName)));
+		assert_eq!(library.list().len(), 1);
+	}
+
+	#[test]
+	fn edits_create_revisions() {
+		let mut library = library_with_set();
+		let (revision, _) =
+			library.edit("quiz", LibraryEdit::AddQuestion(Some(1), question("c"))).unwrap();
+		assert_eq!(revision.revision, 2);
+		assert_eq!(texts(&revision), ["a", "c", "b"]);
+		let (revision, _) =
+			library.edit("quiz", LibraryEdit::AddQuestion(None, question("d"))).unwrap();
+		assert_eq!(texts(&revision), ["a", "c", "b", "d"]);
+		let (revision, _) =
+			library.edit("quiz", LibraryEdit::UpdateQuestion(0, question("e"))).unwrap();
+		assert_eq!(texts(&revision), ["e", "c", "b", "d"]);
+		let (revision, _) = library.edit("quiz", LibraryEdit::DeleteQuestion(1)).unwrap();
+		assert_eq!(texts(&revision), ["e", "b", "d"]);
+		let (revision, _) = library.edit("quiz", LibraryEdit::DuplicateQuestion(2)).unwrap();
+		assert_eq!(texts(&revision), ["e", "b", "d", "d"]);
+		assert_eq!(revision.revision, 6);
+
+		//old revisions stay available
+		assert_eq!(texts(&library.get("quiz", Some(1)).unwrap()), ["a", "b"]);
+		assert_eq!(library.get("quiz", None).unwrap().revision, 6);
+		assert!(library.get("quiz", Some(7)).is_none());
+		assert_eq!(library.revisions("quiz").unwrap().len(), 6);
+		assert_eq!(library.list()[0].revision, Some(6));
+	}
+
+	#[test]
+	fn invalid_edits_change_nothing() {
+		let mut library = library_with_set();
+		for edit in [
+			LibraryEdit::AddQuestion(Some(3), question("c")),
+			LibraryEdit::UpdateQuestion(2, question("c")),
+			LibraryEdit::DeleteQuestion(2),
+			LibraryEdit::DuplicateQuestion(2),
+		] {
+			assert!(matches!(library.edit("quiz", edit), Err(LibraryError::InvalidIndex)));
+		}
+		assert!(matches!(
+			library.edit("other", LibraryEdit::DeleteQuestion(0)),
+			Err(LibraryError::NotFound)
+		));
+		assert_eq!(library.revisions("quiz").unwrap().len(), 1);
+	}
+
+	#[test]
+	fn duplicate_and_delete() {
+		let mut library = library_with_set();
+		library.edit("quiz", LibraryEdit::DeleteQuestion(0)).unwrap();
+		let (revision, _) = library.duplicate("quiz", Some(1), String::from("copy")).unwrap();
+		assert_eq!(revision.revision, 1);
+		assert_eq!(texts(&revision), ["a", "b"]);
+
+		assert!(matches!(library.delete("quiz"), Ok(((), LibraryWrite::Remove(_)))));
+		assert!(library.get("quiz", None).is_none());
+		assert!(matches!(library.delete("quiz"), Err(LibraryError::NotFound)));
+		assert_eq!(library.list().len(), 1);
+	}
+}
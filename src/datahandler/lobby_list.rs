@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::{LobbyListing, LobbyPhase, QuestionSetInfo, QuestionSetMeta};
+
+pub const MAX_PAGE_SIZE: usize = 50; //maximum number of lobbies per page of the lobby list
+
+//criteria for the lobbies shown in the lobby list
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct LobbyFilter {
+	pub search: Option<String>, //part of the admin name or question set name/title
+	pub language: Option<String>,
+	pub phase: Option<LobbyPhase>,
+	pub without_password: bool, //hide password protected lobbies
+}
+
+//listed lobby as shown in the lobby list
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LobbyListEntry {
+	pub lobby_id: String,
+	#[serde(flatten)]
+	pub listing: LobbyListing,
+	pub question_set_meta: Option<QuestionSetMeta>,
+}
+
+//one page of the filtered lobby list
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LobbyListPage {
+	pub lobbies: Vec<LobbyListEntry>,
+	pub total: usize, //number of lobbies matching the filter
+	pub page: usize,
+	pub page_size: usize,
+}
+
+//live update of the lobby list; the lobby is none if it is not listed anymore
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LobbyListUpdate {
+	pub lobby_id: String,
+	pub lobby: Option<LobbyListEntry>,
+}
+
+impl LobbyFilter {
+	pub fn matches(&self, entry: &LobbyListEntry) -> bool {
+		let listing = &entry.listing;
+		if self.without_password && listing.password_protected {
+			return false;
+		}
+		if self.phase.is_some_and(|phase| phase != listing.phase) {
+			return false;
+		}
+		if self.language.as_ref().is_some_and(|language| *language != listing.language) {
+			return false;
+		}
+		if let Some(search) = &self.search {
+			let search = search.to_lowercase();
+			let title = entry.question_set_meta.as_ref().and_then(|meta| meta.title.as_ref());
+			return listing.admin.to_lowercase().contains(&search)
+				|| listing.question_set.to_lowercase().contains(&search)
+				|| title.is_some_and(|title| title.to_lowercase().contains(&search));
+		}
+		true
+	}
+}
+
+//page of the matching lobbies, fullest lobbies first; the UUID keeps the order
+// stable between pages
+pub fn make_page(mut entries: Vec<LobbyListEntry>, page: usize, page_size: usize) -> LobbyListPage {
+	entries.sort_unstable_by(|a, b| {
+		b.listing.players.cmp(&a.listing.players).then_with(|| a.lobby_id.cmp(&b.lobby_id))
+	});
+
+	let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+	let total = entries.len();
+	let lobbies =
+		entries.into_iter().skip(page.saturating_mul(page_size)).take(page_size).collect();
+	LobbyListPage { lobbies, total, page, page_size }
+}
+
+//list entry of a lobby if it is listed and open
+pub fn make_entry(
+	lobby_id: String,
+	listing: LobbyListing,
+	question_sets: &[QuestionSetInfo],
+) -> Option<LobbyListEntry> {
+	if !listing.open {
+		return None;
+	}
+	let question_set_meta = question_sets
+		.iter()
+		.find(|question_set| question_set.name == listing.question_set)
+		.map(|question_set| question_set.meta.clone());
+	Some(LobbyListEntry { lobby_id, listing, question_set_meta })
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+
+	use super::*;
+
+	fn listing(admin: &str, players: usize) -> LobbyListing {
+		LobbyListing {
+			admin: String::from(admin),
+			players,
+			max_players: 0,
+			open: true,
+			password_protected: false,
+			phase: LobbyPhase::Menu,
+			paused: false,
+			current_question: 0,
+			num_questions: 10,
+			question_set: String::from("geography"),
+			question_set_revision: None,
+			language: String::from("en"),
+		}
+	}
+
+	fn entry(lobby_id: &str, listing: LobbyListing) -> LobbyListEntry {
+		let meta =
+			QuestionSetMeta { title: Some(String::from("World Capitals")), ..Default::default() };
+		let question_sets =
+			[QuestionSetInfo::new(String::from("geography"), PathBuf::new(), meta, &[])];
+		make_entry(String::from(lobby_id), listing, &question_sets).unwrap()
+	}
+
+	#[test]
+	fn closed_lobbies_are_not_listed() {
+		let closed = LobbyListing { open: false, ..listing("Alice", 1) };
+		assert!(make_entry(String::from("a"), closed, &[]).is_none());
+		assert!(make_entry(String::from("a"), listing("Alice", 1), &[]).is_some());
+	}
+
+	#[test]
+	fn filter_matches() {
+		let lobby = entry("a", listing("Alice", 1));
+		assert!(LobbyFilter::default().matches(&lobby));
+
+		let search =
+			|search: &str| LobbyFilter { search: Some(String::from(search)), ..Default::default() };
+		assert!(search("alice").matches(&lobby));
+		assert!(search("GEO").matches(&lobby));
+		assert!(search("capitals").matches(&lobby));
+		assert!(!search("bob").matches(&lobby));
+
+		let language = LobbyFilter { language: Some(String::from("de")), ..Default::default() };
+		assert!(!language.matches(&lobby));
+		let phase = LobbyFilter { phase: Some(LobbyPhase::Running), ..Default::default() };
+		assert!(!phase.matches(&lobby));
+		let phase = LobbyFilter { phase: Some(LobbyPhase::Menu), ..Default::default() };
+		assert!(phase.matches(&lobby));
+
+		let without_password = LobbyFilter { without_password: true, ..Default::default() };
+		assert!(without_password.matches(&lobby));
+		let protected = LobbyListing { password_protected: true, ..listing("Alice", 1) };
+		assert!(!without_password.matches(&entry("a", protected)));
+	}
+
+	#[test]
+	fn pages_are_sorted_and_clamped() {
+		let entries: Vec<LobbyListEntry> =
+			(0..60).map(|i| entry(&format!("{:02}", i), listing("Alice", i % 3))).collect();
+
+		let page = make_page(entries.clone(), 0, 10);
+		assert_eq!((page.total, page.page_size, page.lobbies.len()), (60, 10, 10));
+		assert_eq!(page.lobbies[0].lobby_id, "02");
+		assert_eq!(page.lobbies[1].lobby_id, "05");
+		assert!(page.lobbies.iter().all(|entry| entry.listing.players == 2));
+
+		let page = make_page(entries.clone(), 0, 1000);
+		assert_eq!((page.page_size, page.lobbies.len()), (MAX_PAGE_SIZE, MAX_PAGE_SIZE));
+		let page = make_page(entries.clone(), 1, 0);
+		assert_eq!((page.page_size, page.lobbies.len()), (1, 1));
+		let page = make_page(entries.clone(), 1, MAX_PAGE_SIZE);
+		assert_eq!(page.lobbies.len(), 10);
+		let page = make_page(entries, usize::MAX, MAX_PAGE_SIZE);
+		assert!(page.lobbies.is_empty());
+		assert_eq!(page.total, 60);
+	}
+}
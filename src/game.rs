@@ -1,42 +1,106 @@
-use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
-
+use std::{
+	collections::{BTreeMap, HashMap, VecDeque},
+	sync::{
+		atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use actix_web::rt;
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, RwLock};
 
+mod bans;
+mod board;
+mod buzzer;
 mod events;
+mod jokers;
+mod judging;
+mod password;
 mod questions;
+mod reconnect;
+mod ruleset;
 mod state;
-
+mod waiting_room;
+
+use bans::Ban;
+pub use bans::BanInfo;
+use board::BoardState;
+pub use board::{BoardChooser, BoardError, GameMode};
+use buzzer::BuzzerState;
+pub use buzzer::{BuzzError, BuzzerSettings};
 pub use events::Event;
 use events::*;
-pub use questions::{find_question_files, Question, QuestionType};
-use state::LobbyState;
+use jokers::AudiencePoll;
+pub use jokers::{JokerError, JokerResult, JokerType};
+pub use judging::JudgeError;
+use judging::Judging;
+use password::PasswordHash;
+pub use questions::{
+	export_questions, find_question_files, find_question_sets, is_valid_language_code,
+	is_valid_question_set_name, load_question_set, save_question_set, Difficulty, ExportFormat,
+	LocalizedText, Question, QuestionSetFile, QuestionSetInfo, QuestionSetMeta,
+	QuestionTranslation, QuestionType, Round, RoundInfo, RoundTranslation,
+};
+pub use reconnect::ReconnectError;
+pub use ruleset::{Ruleset, RulesetPreset};
+use state::{FinalReveal, GameSnapshot, LobbyState};
+pub use state::{LobbyPhase, Navigation};
+use waiting_room::JoinRequest;
+pub use waiting_room::{JoinError, JoinResult};
 
 //standard parameters for the game
 const INITIAL_MONEY: i64 = 500; //initial amount of money every player owns
 const INITIAL_JOKERS: usize = 3; //number of inital jokers every player gets
-const NORMAL_Q_MONEY: i64 = 500; //money to get when answering a normal question correctly
-const ESTIMATION_Q_MONEY: i64 = 1000; //money to get when winning a estimation question
+const DEFAULT_LANGUAGE: &str = "en"; //language used for questions if players did not choose one
 
 //object for one gameshow lobby; includes all necessary data and methods to
-// interact lock order to avoid deadlocks: admin -> open -> lobby_state ->
-// question_set -> questions -> player_data -> game_events
+// interact lock order to avoid deadlocks: admin -> open -> password -> bans ->
+// join_requests -> reconnect_tokens -> lobby_state ->
+// ruleset -> game_mode -> buzzer_settings -> question_set -> language ->
+// special_jokers -> questions -> rounds -> question_file -> board -> buzzer ->
+// judging -> final_reveal -> snapshots -> player_data -> audience_poll ->
+// game_events
 pub struct Gameshow {
 	//data related to lobby
 	admin: RwLock<(String, String)>, //UUID and name of player that controls the lobby
 	open: RwLock<bool>,              //whether or not the lobby accepts additional players
+	password: RwLock<Option<PasswordHash>>, //needed to join or watch the lobby, if set
+	bans: RwLock<HashMap<String, Ban>>, //players that can not join, by UUID
+	join_requests: RwLock<Vec<JoinRequest>>, //players waiting for the admin to let them in
+	reconnect_tokens: RwLock<HashMap<String, String>>, //player UUIDs by reconnect token
 	param_initial_money: AtomicI64,  //see respective constants
 	param_initial_jokers: AtomicUsize, //see respective constants
-	param_normal_q_money: AtomicI64, //see respective constants
-	param_estimation_q_money: AtomicI64, //see respective constants
+	ruleset: RwLock<Ruleset>,        //scoring rules
+	game_mode: RwLock<GameMode>,     //how the next question is determined
+	buzzer_settings: RwLock<BuzzerSettings>, //whether and how normal questions are buzzed
 	question_set: RwLock<String>,    //name of selected questions
+	question_set_revision: AtomicUsize, //revision of selected library questions, 0 if none
+	language: RwLock<String>,        //default language of the questions
+	special_jokers: RwLock<BTreeMap<JokerType, usize>>, //initial jokers besides 50/50 jokers
+	param_poll_players: AtomicBool,  //whether answered players take part in audience polls
+	listed: AtomicBool,              //whether the lobby is shown in the public lobby list
+	param_max_players: AtomicUsize,  //number of players the lobby admits, 0 for no limit
+	param_approval: AtomicBool,      //whether the admin has to accept players joining
+	param_lock_renames: AtomicBool,  //whether players keep their names while a game runs
 
 	//data related to the game
 	lobby_state: RwLock<LobbyState>,
 	questions: RwLock<Vec<Question>>,
+	rounds: RwLock<Vec<RoundInfo>>, //rounds the questions are grouped in, if any
+	question_file: RwLock<QuestionSetFile>, //questions as loaded, for saving and exporting
 	current_question: AtomicUsize,
+	next_player_id: AtomicUsize, //public player IDs are never reused within a lobby
+	paused: AtomicBool,          //player actions are rejected and timers stopped while paused
+	board: RwLock<BoardState>,   //progress of the game in board mode
+	buzzer: RwLock<BuzzerState>, //progress of the current buzzer question
+	judging: RwLock<Option<Judging>>, //host's verdicts for the current question, if judged
+	final_reveal: RwLock<FinalReveal>,
+	snapshots: RwLock<VecDeque<GameSnapshot>>, //games before the last scoring steps
 	player_data: RwLock<Vec<PlayerData>>,
+	audience_poll: RwLock<Option<AudiencePoll>>,
 	game_events: RwLock<EventManager>,
 }
 
@@ -45,16 +109,39 @@ impl Gameshow {
 		Gameshow {
 			admin: RwLock::new((admin, name)),
 			open: RwLock::new(true),
+			password: RwLock::new(None),
+			bans: RwLock::new(HashMap::new()),
+			join_requests: RwLock::new(Vec::new()),
+			reconnect_tokens: RwLock::new(HashMap::new()),
 			param_initial_money: AtomicI64::new(INITIAL_MONEY),
 			param_initial_jokers: AtomicUsize::new(INITIAL_JOKERS),
-			param_normal_q_money: AtomicI64::new(NORMAL_Q_MONEY),
-			param_estimation_q_money: AtomicI64::new(ESTIMATION_Q_MONEY),
+			ruleset: RwLock::new(Ruleset::default()),
+			game_mode: RwLock::new(GameMode::Sequential),
+			buzzer_settings: RwLock::new(BuzzerSettings::default()),
 			question_set: RwLock::new(String::new()),
+			question_set_revision: AtomicUsize::new(0),
+			language: RwLock::new(String::from(DEFAULT_LANGUAGE)),
+			special_jokers: RwLock::new(BTreeMap::new()),
+			param_poll_players: AtomicBool::new(false),
+			listed: AtomicBool::new(false),
+			param_max_players: AtomicUsize::new(0),
+			param_approval: AtomicBool::new(false),
+			param_lock_renames: AtomicBool::new(false),
 
 			lobby_state: RwLock::new(LobbyState::Menu(false)),
 			questions: RwLock::new(Vec::new()),
+			rounds: RwLock::new(Vec::new()),
+			question_file: RwLock::new(QuestionSetFile::Questions(Vec::new())),
 			current_question: AtomicUsize::new(0),
+			next_player_id: AtomicUsize::new(1),
+			paused: AtomicBool::new(false),
+			board: RwLock::new(BoardState::default()),
+			buzzer: RwLock::new(BuzzerState::default()),
+			judging: RwLock::new(None),
+			final_reveal: RwLock::new(FinalReveal::default()),
+			snapshots: RwLock::new(VecDeque::new()),
 			player_data: RwLock::new(Vec::new()),
+			audience_poll: RwLock::new(None),
 			game_events: RwLock::new(EventManager::new()),
 		}
 	}
@@ -71,19 +158,101 @@ impl Gameshow {
 
 	async fn generate_lobby_update(&self) {
 		let lobby_open = self.is_open().await;
+		let password_protected = self.has_password().await;
+		let ruleset = self.get_ruleset().await;
+		let game_mode = self.get_game_mode().await;
+		let buzzer = self.get_buzzer_settings().await;
 		let question_set = self.get_question_set().await;
+		let language = self.get_language().await;
+		let special_jokers = self.get_special_jokers().await;
 		//send LobbySettingsUpdate to clients
 		let event = EventType::LobbySettingsUpdate(EventLobbySettingsUpdate {
 			open: lobby_open,
+			listed: self.is_listed(),
+			password_protected,
+			max_players: self.get_max_players(),
+			approval_required: self.get_approval_required(),
+			lock_renames: self.get_lock_renames(),
 			initial_money: self.get_initial_money(),
 			initial_jokers: self.get_initial_jokers(),
-			normal_q_money: self.get_normal_q_money(),
-			estimation_q_money: self.get_estimation_q_money(),
+			normal_q_money: ruleset.normal_q_money,
+			estimation_q_money: ruleset.estimation_q_money,
+			ruleset,
+			game_mode,
+			buzzer,
 			question_set,
+			question_set_revision: self.get_question_set_revision(),
+			language,
+			special_jokers,
+			audience_poll_includes_players: self.get_audience_poll_includes_players(),
 		});
 		self.game_events.write().await.add(event);
 	}
 
+	async fn generate_join_requests_update(&self) {
+		let admin_uuid = self.get_admin_uuid().await;
+		let requests_access = self.join_requests.read().await;
+		let requests = (*requests_access)
+			.iter()
+			.map(|request| JoinRequestInfo { id: request.id, name: request.name.clone() })
+			.collect();
+
+		//send JoinRequestsUpdate to the admin only
+		let event = EventType::JoinRequestsUpdate(EventJoinRequestsUpdate { requests });
+		self.game_events.write().await.add_private(&admin_uuid, event);
+	}
+
+	async fn generate_judging_update(&self) {
+		//collect the answers with their current verdicts
+		let admin_uuid = self.get_admin_uuid().await;
+		let question_id = self.current_question.load(Ordering::Relaxed);
+		let correct_answer = (*self.questions.read().await)[question_id - 1].correct_answer;
+		let judging_access = self.judging.read().await;
+		let player_access = self.player_data.read().await;
+		let answers = (*player_access)
+			.iter()
+			.filter(|player| player.is_active())
+			.map(|player| JudgedAnswer {
+				id: player.id,
+				name: player.name.clone(),
+				answer: player.answer,
+				correct: judging::is_correct(judging_access.as_ref(), player, correct_answer),
+				judged: judging::verdict(judging_access.as_ref(), player).is_some(),
+			})
+			.collect();
+
+		//send JudgingUpdate to the admin only
+		let event = EventType::JudgingUpdate(EventJudgingUpdate { answers });
+		self.game_events.write().await.add_private(&admin_uuid, event);
+	}
+
+	async fn generate_poll_update(&self) -> Option<Vec<usize>> {
+		//count the votes, including answers of players who did not request the poll
+		let player_access = self.player_data.read().await;
+		let poll_access = self.audience_poll.read().await;
+		let poll = (*poll_access).as_ref()?;
+		let player_answers: Vec<usize> = if self.get_audience_poll_includes_players() {
+			(*player_access)
+				.iter()
+				.filter(|player| !poll.requesters.contains(&player.uuid))
+				.map(|player| player.answer)
+				.collect()
+		} else {
+			Vec::new()
+		};
+		let histogram = poll.histogram(&player_answers);
+
+		//send AudiencePollUpdate to the requesting players only
+		let mut events = self.game_events.write().await;
+		for requester in poll.requesters.iter() {
+			let event = EventType::AudiencePollUpdate(EventAudiencePollUpdate {
+				histogram: histogram.clone(),
+			});
+			events.add_private(requester, event);
+		}
+		Some(histogram)
+	}
+
 	pub async fn get_admin_uuid(&self) -> String {
 		let admin_access = self.admin.read().await;
 		admin_access.0.clone()
@@ -108,6 +277,31 @@ impl Gameshow {
 		*open_access
 	}
 
+	pub async fn has_password(&self) -> bool {
+		self.password.read().await.is_some()
+	}
+
+	//whether the password is correct, any password is if none is set
+	pub async fn check_password(&self, password: Option<&str>) -> bool {
+		let password_access = self.password.read().await;
+		match (&*password_access, password) {
+			(None, _) => true,
+			(Some(hash), Some(password)) => hash.verify(password),
+			(Some(_), None) => false,
+		}
+	}
+
+	//whether the player may see the lobby's events and player data: the admin
+	// and joined players always can, anyone else needs the password
+	pub async fn may_watch(&self, uuid: Option<&str>, password: Option<&str>) -> bool {
+		if let Some(uuid) = uuid {
+			if self.get_admin_uuid().await == uuid || self.is_joined(uuid).await {
+				return true;
+			}
+		}
+		self.check_password(password).await
+	}
+
 	pub fn get_initial_money(&self) -> i64 {
 		self.param_initial_money.load(Ordering::Relaxed)
 	}
@@ -116,12 +310,18 @@ impl Gameshow {
 		self.param_initial_jokers.load(Ordering::Relaxed)
 	}
 
-	pub fn get_normal_q_money(&self) -> i64 {
-		self.param_normal_q_money.load(Ordering::Relaxed)
+	pub async fn get_ruleset(&self) -> Ruleset {
+		let ruleset_access = self.ruleset.read().await;
+		(*ruleset_access).clone()
+	}
+
+	pub async fn get_game_mode(&self) -> GameMode {
+		*self.game_mode.read().await
 	}
 
-	pub fn get_estimation_q_money(&self) -> i64 {
-		self.param_estimation_q_money.load(Ordering::Relaxed)
+	pub async fn get_buzzer_settings(&self) -> BuzzerSettings {
+		let buzzer_settings_access = self.buzzer_settings.read().await;
+		(*buzzer_settings_access).clone()
 	}
 
 	pub async fn get_question_set(&self) -> String {
@@ -129,6 +329,74 @@ impl Gameshow {
 		(*question_set_access).clone()
 	}
 
+	pub fn get_question_set_revision(&self) -> Option<usize> {
+		match self.question_set_revision.load(Ordering::Relaxed) {
+			0 => None,
+			revision => Some(revision),
+		}
+	}
+
+	pub async fn get_language(&self) -> String {
+		let language_access = self.language.read().await;
+		(*language_access).clone()
+	}
+
+	pub async fn get_special_jokers(&self) -> BTreeMap<JokerType, usize> {
+		let special_jokers_access = self.special_jokers.read().await;
+		(*special_jokers_access).clone()
+	}
+
+	pub fn get_audience_poll_includes_players(&self) -> bool {
+		self.param_poll_players.load(Ordering::Relaxed)
+	}
+
+	pub fn get_max_players(&self) -> usize {
+		self.param_max_players.load(Ordering::Relaxed)
+	}
+
+	pub fn get_approval_required(&self) -> bool {
+		self.param_approval.load(Ordering::Relaxed)
+	}
+
+	pub fn get_lock_renames(&self) -> bool {
+		self.param_lock_renames.load(Ordering::Relaxed)
+	}
+
+	pub fn is_listed(&self) -> bool {
+		self.listed.load(Ordering::Relaxed)
+	}
+
+	//summary of the lobby for the public lobby list
+	pub async fn get_listing(&self) -> LobbyListing {
+		let admin = self.get_admin_name().await;
+		let open = self.is_open().await;
+		let password_protected = self.has_password().await;
+		let phase = self.lobby_state.read().await.phase();
+		let question_set = self.get_question_set().await;
+		let language = self.get_language().await;
+		let num_questions = self.questions.read().await.len();
+		let players = self.player_data.read().await.len();
+		LobbyListing {
+			admin,
+			players,
+			max_players: self.get_max_players(),
+			open,
+			password_protected,
+			phase,
+			paused: self.is_paused(),
+			current_question: self.current_question.load(Ordering::Relaxed),
+			num_questions,
+			question_set,
+			question_set_revision: self.get_question_set_revision(),
+			language,
+		}
+	}
+
+	//announce changes of the lobby to the server-wide lobby list
+	pub fn set_listing_updates(&mut self, lobby_id: String, sender: broadcast::Sender<String>) {
+		self.game_events.get_mut().set_listing_updates(lobby_id, sender);
+	}
+
 	pub async fn set_open(&self, open: bool) -> &Self {
 		{
 			//set new preference
@@ -142,6 +410,61 @@ impl Gameshow {
 		self
 	}
 
+	//set or, if empty, remove the lobby password
+	pub async fn set_password(&self, password: &str) -> &Self {
+		{
+			let mut password_access = self.password.write().await;
+			(*password_access) =
+				if password.is_empty() { None } else { Some(PasswordHash::new(password)) };
+		}
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_max_players(&self, max_players: usize) -> &Self {
+		//set new preference
+		self.param_max_players.store(max_players, Ordering::Relaxed);
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_approval_required(&self, approval_required: bool) -> &Self {
+		//set new preference
+		self.param_approval.store(approval_required, Ordering::Relaxed);
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_lock_renames(&self, lock_renames: bool) -> &Self {
+		//set new preference
+		self.param_lock_renames.store(lock_renames, Ordering::Relaxed);
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_listed(&self, listed: bool) -> &Self {
+		//set new preference
+		self.listed.store(listed, Ordering::Relaxed);
+		self.game_events.write().await.set_listed(listed);
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
 	pub async fn update_preferences(
 		&self,
 		initial_money: i64,
@@ -160,12 +483,32 @@ impl Gameshow {
 
 		self.param_initial_jokers.store(initial_jokers, Ordering::Relaxed);
 
-		if normal_q_money > 0 {
-			self.param_normal_q_money.store(normal_q_money, Ordering::Relaxed);
+		{
+			let mut ruleset_access = self.ruleset.write().await;
+			if normal_q_money > 0 {
+				ruleset_access.normal_q_money = normal_q_money;
+			}
+
+			if estimation_q_money > 0 {
+				ruleset_access.estimation_q_money = estimation_q_money;
+			}
+		}
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_ruleset(&self, ruleset: Ruleset) -> &Self {
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::Menu(false) {
+			return self;
 		}
 
-		if estimation_q_money > 0 {
-			self.param_estimation_q_money.store(estimation_q_money, Ordering::Relaxed);
+		{
+			let mut ruleset_access = self.ruleset.write().await;
+			(*ruleset_access) = ruleset;
 		}
 
 		//send update event to clients
@@ -174,43 +517,148 @@ impl Gameshow {
 		self
 	}
 
-	pub async fn set_question_set(&self, question_set: &str) -> std::io::Result<&Self> {
+	pub async fn set_game_mode(&self, game_mode: GameMode) -> &Self {
 		//ensure current lobby state is correct
 		if *self.lobby_state.read().await != LobbyState::Menu(false) {
-			return Ok(self);
+			return self;
 		}
 
-		//preload question set (if not custom)
-		let mut questions = Vec::new();
-		if question_set != "custom" {
-			let question_sets = questions::find_question_files()?;
-			for (name, file) in question_sets.iter() {
-				if name == question_set {
-					questions = questions::read_questions(file)?;
-					break;
-				}
-			}
+		{
+			let mut game_mode_access = self.game_mode.write().await;
+			(*game_mode_access) = game_mode;
+		}
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_buzzer_settings(&self, buzzer_settings: BuzzerSettings) -> &Self {
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::Menu(false) {
+			return self;
+		}
+
+		{
+			let mut buzzer_settings_access = self.buzzer_settings.write().await;
+			(*buzzer_settings_access) = buzzer_settings;
+		}
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_language(&self, language: &str) -> &Self {
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::Menu(false) {
+			return self;
+		}
+
+		{
+			let mut language_access = self.language.write().await;
+			(*language_access) = String::from(language);
+		}
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_special_jokers(
+		&self,
+		mut special_jokers: BTreeMap<JokerType, usize>,
+	) -> &Self {
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::Menu(false) {
+			return self;
+		}
+
+		//50/50 jokers are set by the initial jokers
+		special_jokers.remove(&JokerType::FiftyFifty);
+		{
+			let mut special_jokers_access = self.special_jokers.write().await;
+			(*special_jokers_access) = special_jokers;
+		}
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_audience_poll_includes_players(&self, include_players: bool) -> &Self {
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::Menu(false) {
+			return self;
+		}
+
+		self.param_poll_players.store(include_players, Ordering::Relaxed);
+
+		//send update event to clients
+		self.generate_lobby_update().await;
+
+		self
+	}
+
+	pub async fn set_question_set(
+		&self,
+		question_set: &str,
+		question_file: QuestionSetFile,
+	) -> &Self {
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::Menu(false) {
+			return self;
 		}
 
+		self.store_question_set(question_set, None, question_file).await;
+
+		self
+	}
+
+	pub async fn set_library_question_set(
+		&self,
+		question_set: &str,
+		revision: usize,
+		meta: QuestionSetMeta,
+		questions: Vec<Question>,
+	) -> &Self {
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::Menu(false) {
+			return self;
+		}
+
+		let question_file = QuestionSetFile::WithMeta { meta, questions };
+		self.store_question_set(question_set, Some(revision), question_file).await;
+
+		self
+	}
+
+	async fn store_question_set(
+		&self,
+		question_set: &str,
+		revision: Option<usize>,
+		question_file: QuestionSetFile,
+	) {
 		{
 			//update preference and save questions (if not custom)
 			let mut question_set_access = self.question_set.write().await;
 			(*question_set_access) = String::from(question_set);
+			self.question_set_revision.store(revision.unwrap_or(0), Ordering::Relaxed);
 
 			if (*question_set_access) != "custom" {
-				self.current_question.store(0, Ordering::Relaxed);
-				let mut questions_access = self.questions.write().await;
-				(*questions_access) = questions;
+				self.store_questions(question_file).await;
 			}
 		}
 
 		//send update event to clients
 		self.generate_lobby_update().await;
-
-		Ok(self)
 	}
 
-	pub async fn set_questions(&self, questions: Vec<Question>) -> std::io::Result<&Self> {
+	pub async fn set_questions(&self, question_file: QuestionSetFile) -> std::io::Result<&Self> {
 		//ensure current lobby state is correct and question_set allows custom
 		// questions
 		if *self.lobby_state.read().await != LobbyState::Menu(false)
@@ -219,11 +667,24 @@ impl Gameshow {
 			return Ok(self);
 		}
 
+		self.store_questions(question_file).await;
+
+		Ok(self)
+	}
+
+	async fn store_questions(&self, question_file: QuestionSetFile) {
 		self.current_question.store(0, Ordering::Relaxed);
+		let (questions, rounds) = question_file.clone().into_questions_and_rounds();
 		let mut questions_access = self.questions.write().await;
 		(*questions_access) = questions;
+		let mut rounds_access = self.rounds.write().await;
+		(*rounds_access) = rounds;
+		let mut question_file_access = self.question_file.write().await;
+		(*question_file_access) = question_file;
+	}
 
-		Ok(self)
+	pub async fn get_question_file(&self) -> QuestionSetFile {
+		self.question_file.read().await.clone()
 	}
 
 	pub async fn get_player_data(&self) -> Vec<PublicPlayerData> {
@@ -238,39 +699,74 @@ impl Gameshow {
 		self.game_events.read().await.subscribe()
 	}
 
+	pub async fn get_private_events(&self, uuid: &str) -> Vec<Event> {
+		self.game_events.read().await.get_private(uuid)
+	}
+
+	pub async fn subscribe_private_events(&self) -> broadcast::Receiver<(String, Event)> {
+		self.game_events.read().await.subscribe_private()
+	}
+
 	#[allow(dead_code)]
 	pub async fn get_event_subscribers(&self) -> usize {
 		self.game_events.read().await.get_subscribers()
 	}
 
-	pub async fn join(&self, uuid: &str, mut name: String) -> Option<String> {
-		//check if already joined and return true if so; also check if name is already
-		// in use
-		let mut name_in_use = false;
+	pub async fn join(
+		&self,
+		uuid: &str,
+		name: String,
+		language: Option<String>,
+	) -> Result<JoinResult, JoinError> {
+		//banned players can not join at all
+		if self.is_banned(uuid).await {
+			return Err(JoinError::Banned);
+		}
+
+		//check if already joined and return true if so (updating the language if
+		// given)
 		{
-			let player_access = self.player_data.read().await;
-			for player in (*player_access).iter() {
-				if player.uuid == uuid {
-					return Some(player.name.clone());
-				} else if player.name == name {
-					name_in_use = true;
+			let mut player_access = self.player_data.write().await;
+			if let Some(player) = (*player_access).iter_mut().find(|player| player.uuid == uuid) {
+				let name = player.name.clone();
+				if let Some(language) = language {
+					player.language = language;
+					//send PlayerListUpdate to clients
+					let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+						player_data: make_public_player_data(&player_access),
+					});
+					self.game_events.write().await.add(event);
 				}
+				return Ok(JoinResult::Joined(name));
 			}
 		}
+		let language = match language {
+			Some(language) => language,
+			None => self.get_language().await,
+		};
 
 		//if not already joined, check if allowed to join
 		if self.get_admin_uuid().await == uuid {
 			//admin can always join with its name
+			let special_jokers = self.get_special_jokers().await;
 			let mut player_access = self.player_data.write().await;
 			let new_player = PlayerData {
 				uuid: String::from(uuid),
+				id: self.new_player_id(),
 				name: name.clone(),
 				jokers: self.get_initial_jokers(),
+				special_jokers,
 				money: self.get_initial_money(),
+				language,
 
 				money_bet: 0,
-				vs_player: String::new(),
+				vs_player: 0,
 				answer: 0,
+				used_jokers: Vec::new(),
+				eliminated: false,
+				round_start_money: 0,
+				round_correct_answers: 0,
+				final_wager: None,
 			};
 			(*player_access).push(new_player);
 			//send PlayerListUpdate to clients
@@ -279,55 +775,187 @@ impl Gameshow {
 			});
 			self.game_events.write().await.add(event);
 			//return name
-			Some(name)
-		} else if self.is_open().await {
-			//others need to have unique name (from others and from admin)
-			let admin_name = self.get_admin_name().await;
-			let mut player_access = self.player_data.write().await;
-			//make sure the name is unique
-			while name_in_use || name == admin_name {
+			Ok(JoinResult::Joined(name))
+		} else if let Some(name) = self.get_join_request_name(uuid).await {
+			//asking again does not queue the player twice
+			Ok(JoinResult::Pending(name))
+		} else if !self.is_open().await {
+			Err(JoinError::Closed)
+		} else if self.is_full().await {
+			Err(JoinError::Full)
+		} else if self.get_approval_required() {
+			Ok(JoinResult::Pending(self.request_join(uuid, name, language).await))
+		} else {
+			let id = self.new_player_id();
+			Ok(JoinResult::Joined(self.add_player(uuid, id, name, language).await?))
+		}
+	}
+
+	//add a player other than the admin, returns the player's unique name; the
+	// player limit is checked while holding the lock, so concurrent joins can not
+	// exceed it
+	async fn add_player(
+		&self,
+		uuid: &str,
+		id: usize,
+		mut name: String,
+		language: String,
+	) -> Result<String, JoinError> {
+		let admin_name = self.get_admin_name().await;
+		let special_jokers = self.get_special_jokers().await;
+		let mut player_access = self.player_data.write().await;
+		let max_players = self.get_max_players();
+		if max_players != 0 && (*player_access).len() >= max_players {
+			return Err(JoinError::Full);
+		}
+		//others need to have unique name (from others and from admin)
+		while name == admin_name || (*player_access).iter().any(|player| player.name == name) {
+			name += &generate_random_string(2);
+		}
+		let new_player = PlayerData {
+			uuid: String::from(uuid),
+			id,
+			name: name.clone(),
+			jokers: self.get_initial_jokers(),
+			special_jokers,
+			money: self.get_initial_money(),
+			language,
+
+			money_bet: 0,
+			vs_player: 0,
+			answer: 0,
+			used_jokers: Vec::new(),
+			eliminated: false,
+			round_start_money: 0,
+			round_correct_answers: 0,
+			final_wager: None,
+		};
+		(*player_access).push(new_player);
+		//send PlayerListUpdate to clients
+		let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+			player_data: make_public_player_data(&player_access),
+		});
+		self.game_events.write().await.add(event);
+		Ok(name)
+	}
+
+	//put the player into the waiting room, returns the player's unique name
+	async fn request_join(&self, uuid: &str, mut name: String, language: String) -> String {
+		let admin_name = self.get_admin_name().await;
+		{
+			let mut requests_access = self.join_requests.write().await;
+			let player_access = self.player_data.read().await;
+			while name == admin_name
+				|| (*requests_access).iter().any(|request| request.name == name)
+				|| (*player_access).iter().any(|player| player.name == name)
+			{
 				name += &generate_random_string(2);
-				name_in_use = false;
-				for player in (*player_access).iter() {
-					if player.name == name {
-						name_in_use = true;
-						break;
-					}
-				}
 			}
-			let new_player = PlayerData {
+			(*requests_access).push(JoinRequest {
 				uuid: String::from(uuid),
+				id: self.new_player_id(),
 				name: name.clone(),
-				jokers: self.get_initial_jokers(),
-				money: self.get_initial_money(),
-
-				money_bet: 0,
-				vs_player: String::new(),
-				answer: 0,
-			};
-			(*player_access).push(new_player);
-			//send PlayerListUpdate to clients
-			let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
-				player_data: make_public_player_data(&player_access),
+				language,
 			});
-			self.game_events.write().await.add(event);
-			//return name
-			Some(name)
-		} else {
-			None
 		}
+
+		self.generate_join_requests_update().await;
+		name
 	}
 
-	pub async fn leave(&self, uuid: &str) -> bool {
-		let contained;
-		{
-			let mut player_access = self.player_data.write().await;
-			contained = (*player_access).iter().any(|player| player.uuid == uuid);
-			if contained {
-				(*player_access).retain(|player| player.uuid != uuid);
-				//send PlayerListUpdate to clients
-				let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
-					player_data: make_public_player_data(&player_access),
+	async fn get_join_request_name(&self, uuid: &str) -> Option<String> {
+		let requests_access = self.join_requests.read().await;
+		(*requests_access)
+			.iter()
+			.find(|request| request.uuid == uuid)
+			.map(|request| request.name.clone())
+	}
+
+	//the player keeps the ID from the join request when accepted
+	fn new_player_id(&self) -> usize {
+		self.next_player_id.fetch_add(1, Ordering::Relaxed)
+	}
+
+	//whether no more players can join
+	pub async fn is_full(&self) -> bool {
+		let max_players = self.get_max_players();
+		max_players != 0 && self.player_data.read().await.len() >= max_players
+	}
+
+	pub async fn accept_join_request(&self, player_id: usize) -> Result<String, JoinError> {
+		let (index, request) = {
+			let mut requests_access = self.join_requests.write().await;
+			let index = (*requests_access)
+				.iter()
+				.position(|request| request.id == player_id)
+				.ok_or(JoinError::RequestNotFound)?;
+			(index, (*requests_access).remove(index))
+		};
+
+		let res = self
+			.add_player(&request.uuid, request.id, request.name.clone(), request.language.clone())
+			.await;
+		let name = match res {
+			Ok(name) => name,
+			Err(err) => {
+				//the player keeps waiting if the lobby is full
+				let mut requests_access = self.join_requests.write().await;
+				let index = index.min((*requests_access).len());
+				(*requests_access).insert(index, request);
+				return Err(err);
+			}
+		};
+		self.generate_join_requests_update().await;
+		//tell the player, the name might have changed while waiting
+		let event = EventType::JoinRequestAnswered(EventJoinRequestAnswered {
+			accepted: true,
+			name: name.clone(),
+		});
+		self.game_events.write().await.add_private(&request.uuid, event);
+		Ok(name)
+	}
+
+	pub async fn reject_join_request(&self, player_id: usize) -> bool {
+		let request = {
+			let mut requests_access = self.join_requests.write().await;
+			let index = (*requests_access).iter().position(|request| request.id == player_id);
+			match index {
+				Some(index) => (*requests_access).remove(index),
+				None => return false,
+			}
+		};
+
+		self.generate_join_requests_update().await;
+		let event = EventType::JoinRequestAnswered(EventJoinRequestAnswered {
+			accepted: false,
+			name: request.name,
+		});
+		self.game_events.write().await.add_private(&request.uuid, event);
+		true
+	}
+
+	pub async fn leave(&self, uuid: &str) -> bool {
+		//players still waiting withdraw their join request
+		let requested = {
+			let mut requests_access = self.join_requests.write().await;
+			let count = (*requests_access).len();
+			(*requests_access).retain(|request| request.uuid != uuid);
+			(*requests_access).len() != count
+		};
+		if requested {
+			self.generate_join_requests_update().await;
+			return true;
+		}
+
+		let contained;
+		{
+			let mut player_access = self.player_data.write().await;
+			contained = (*player_access).iter().any(|player| player.uuid == uuid);
+			if contained {
+				(*player_access).retain(|player| player.uuid != uuid);
+				//send PlayerListUpdate to clients
+				let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+					player_data: make_public_player_data(&player_access),
 				});
 				self.game_events.write().await.add(event);
 			}
@@ -341,13 +969,13 @@ impl Gameshow {
 		// uuid); let contained = removed.count() != 0;
 	}
 
-	pub async fn kick_player(&self, name: &str) -> bool {
+	pub async fn kick_player(&self, player_id: usize) -> bool {
 		let contained;
 		{
 			let mut player_access = self.player_data.write().await;
-			contained = (*player_access).iter().any(|player| player.name == name);
+			contained = (*player_access).iter().any(|player| player.id == player_id);
 			if contained {
-				(*player_access).retain(|player| player.name != name);
+				(*player_access).retain(|player| player.id != player_id);
 				//send PlayerListUpdate to clients
 				let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
 					player_data: make_public_player_data(&player_access),
@@ -360,17 +988,62 @@ impl Gameshow {
 		contained
 
 		//in the future when drain_filter is not experimental anymore
-		//let removed = (*player_access).drain_filter(|player| player.name !=
-		// name); let contained = removed.count() != 0;
+		//let removed = (*player_access).drain_filter(|player| player.id !=
+		// player_id); let contained = removed.count() != 0;
+	}
+
+	pub async fn is_banned(&self, uuid: &str) -> bool {
+		//expired bans are removed
+		let mut bans_access = self.bans.write().await;
+		(*bans_access).retain(|_, ban| ban.is_active());
+		(*bans_access).contains_key(uuid)
 	}
 
-	pub async fn set_player_attributes(&self, name: &str, money: i64, jokers: usize) -> bool {
+	pub async fn get_bans(&self) -> Vec<BanInfo> {
+		let mut bans_access = self.bans.write().await;
+		(*bans_access).retain(|_, ban| ban.is_active());
+		(*bans_access).values().map(Ban::info).collect()
+	}
+
+	pub async fn ban_player(&self, player_id: usize, duration: Option<Duration>) -> bool {
+		//the admin can not be banned
+		let admin_uuid = self.get_admin_uuid().await;
+		let (uuid, name) = match (*self.player_data.read().await)
+			.iter()
+			.find(|player| player.id == player_id && player.uuid != admin_uuid)
+		{
+			Some(player) => (player.uuid.clone(), player.name.clone()),
+			None => return false,
+		};
+
+		self.bans.write().await.insert(uuid, Ban::new(player_id, name, duration));
+		self.kick_player(player_id).await
+	}
+
+	pub async fn unban_player(&self, player_id: usize) -> bool {
+		let mut bans_access = self.bans.write().await;
+		let num_bans = (*bans_access).len();
+		(*bans_access).retain(|_, ban| ban.player_id != player_id);
+		(*bans_access).len() != num_bans
+	}
+
+	pub async fn set_player_attributes(
+		&self,
+		player_id: usize,
+		money: i64,
+		jokers: usize,
+		special_jokers: Option<BTreeMap<JokerType, usize>>,
+	) -> bool {
 		let mut player_access = self.player_data.write().await;
 		let mut contained = false;
 		(*player_access).iter_mut().for_each(|player| {
-			if player.name == name {
+			if player.id == player_id {
 				player.money = money;
 				player.jokers = jokers;
+				if let Some(special_jokers) = &special_jokers {
+					player.special_jokers = special_jokers.clone();
+					player.special_jokers.remove(&JokerType::FiftyFifty);
+				}
 				contained = true;
 			}
 		});
@@ -384,10 +1057,145 @@ impl Gameshow {
 		contained
 	}
 
+	//whether the lobby would take over a new name of the player (or admin)
+	pub async fn may_rename(&self) -> bool {
+		!self.get_lock_renames() || matches!(*self.lobby_state.read().await, LobbyState::Menu(_))
+	}
+
+	//take over a new name of the player (or admin), returns the name in this
+	// lobby, which is changed to be unique like when joining
+	pub async fn rename(&self, uuid: &str, mut name: String) -> Option<String> {
+		let mut admin_access = self.admin.write().await;
+		let is_admin = admin_access.0 == uuid;
+		let mut player_access = self.player_data.write().await;
+		if !is_admin && !(*player_access).iter().any(|player| player.uuid == uuid) {
+			return None;
+		}
+
+		//others need to have unique name (from others and from admin)
+		while (!is_admin && name == admin_access.1)
+			|| (*player_access).iter().any(|player| player.uuid != uuid && player.name == name)
+		{
+			name += &generate_random_string(2);
+		}
+		if is_admin {
+			admin_access.1 = name.clone();
+		}
+		if let Some(player) = (*player_access).iter_mut().find(|player| player.uuid == uuid) {
+			player.name = name.clone();
+		}
+
+		//send PlayerListUpdate to clients
+		let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+			player_data: make_public_player_data(&player_access),
+		});
+		let mut events_access = self.game_events.write().await;
+		events_access.add(event);
+		if is_admin {
+			events_access.notify_listing();
+		}
+		Some(name)
+	}
+
+	//token to continue as the player (or admin) on another device, replacing
+	// the player's previous token
+	pub async fn create_reconnect_token(&self, uuid: &str) -> Option<String> {
+		if self.get_admin_uuid().await != uuid && !self.is_joined(uuid).await {
+			return None;
+		}
+		let mut tokens_access = self.reconnect_tokens.write().await;
+		(*tokens_access).retain(|_, player| player != uuid);
+		let mut token = String::new();
+		while token.is_empty() || (*tokens_access).contains_key(&token) {
+			token = generate_random_string(reconnect::TOKEN_LENGTH);
+		}
+		(*tokens_access).insert(token.clone(), uuid.to_owned());
+		Some(token)
+	}
+
+	pub async fn is_valid_reconnect_token(&self, token: &str) -> bool {
+		self.reconnect_tokens.read().await.contains_key(token)
+	}
+
+	//continue as the token's player with the new UUID; the old UUID loses
+	// access to the player, so the old session is revoked
+	pub async fn redeem_reconnect_token(
+		&self,
+		token: &str,
+		uuid: &str,
+	) -> Result<(), ReconnectError> {
+		if self.get_admin_uuid().await == uuid || self.is_joined(uuid).await {
+			return Err(ReconnectError::AlreadyJoined);
+		}
+		let old_uuid = self
+			.reconnect_tokens
+			.write()
+			.await
+			.remove(token)
+			.ok_or(ReconnectError::InvalidToken)?;
+
+		//move every reference to the player over to the new UUID; bans and join
+		// requests can not refer to it, as the player is still in the lobby
+		let mut admin_access = self.admin.write().await;
+		let mut board_access = self.board.write().await;
+		let mut buzzer_access = self.buzzer.write().await;
+		let mut judging_access = self.judging.write().await;
+		let mut final_reveal_access = self.final_reveal.write().await;
+		let mut snapshots_access = self.snapshots.write().await;
+		let mut player_access = self.player_data.write().await;
+		let mut poll_access = self.audience_poll.write().await;
+		let mut events = self.game_events.write().await;
+		if admin_access.0 != old_uuid
+			&& !(*player_access).iter().any(|player| player.uuid == old_uuid)
+		{
+			//the player left the lobby since creating the token
+			return Err(ReconnectError::InvalidToken);
+		}
+		reconnect::rebind(&mut admin_access.0, &old_uuid, uuid);
+		board_access.rebind(&old_uuid, uuid);
+		buzzer_access.rebind(&old_uuid, uuid);
+		if let Some(judging) = judging_access.as_mut() {
+			if let Some(verdict) = judging.verdicts.remove(&old_uuid) {
+				judging.verdicts.insert(uuid.to_owned(), verdict);
+			}
+		}
+		final_reveal_access.rebind(&old_uuid, uuid);
+		for snapshot in snapshots_access.iter_mut() {
+			snapshot.board.rebind(&old_uuid, uuid);
+			for player in snapshot.player_data.iter_mut() {
+				reconnect::rebind(&mut player.uuid, &old_uuid, uuid);
+			}
+		}
+		for player in (*player_access).iter_mut() {
+			reconnect::rebind(&mut player.uuid, &old_uuid, uuid);
+		}
+		if let Some(poll) = poll_access.as_mut() {
+			for requester in poll.requesters.iter_mut() {
+				reconnect::rebind(requester, &old_uuid, uuid);
+			}
+			if let Some(vote) = poll.votes.remove(&old_uuid) {
+				poll.votes.insert(uuid.to_owned(), vote);
+			}
+		}
+		events.rebind_private(&old_uuid, uuid);
+		//tell the old device it is not connected anymore
+		events.add_private(&old_uuid, EventType::SessionMoved(EventSessionMoved {}));
+		Ok(())
+	}
+
 	pub async fn is_joined(&self, uuid: &str) -> bool {
 		self.player_data.read().await.iter().any(|player| player.uuid == uuid)
 	}
 
+	pub async fn get_player_id(&self, uuid: &str) -> Option<usize> {
+		self.player_data
+			.read()
+			.await
+			.iter()
+			.find(|player| player.uuid == uuid)
+			.map(|player| player.id)
+	}
+
 	pub async fn get_player_name(&self, uuid: &str) -> Option<String> {
 		for player in self.player_data.read().await.iter() {
 			if player.uuid == uuid {
@@ -406,25 +1214,25 @@ impl Gameshow {
 		None
 	}
 
-	pub async fn get_player_jokers(&self, uuid: &str) -> Option<usize> {
-		for player in self.player_data.read().await.iter() {
-			if player.uuid == uuid {
-				return Some(player.jokers);
-			}
-		}
-		None
+	pub async fn is_eliminated(&self, uuid: &str) -> bool {
+		self.player_data.read().await.iter().any(|player| player.uuid == uuid && player.eliminated)
 	}
 
-	pub async fn is_valid_vs_player(&self, vs_player: &str) -> bool {
+	pub async fn is_valid_vs_player(&self, vs_player: usize) -> bool {
 		for player in self.player_data.read().await.iter() {
-			if player.name == vs_player {
-				return true;
+			if player.id == vs_player {
+				return player.is_active();
 			}
 		}
 		false
 	}
 
 	pub async fn bet(&self, uuid: &str, money_bet: i64) -> bool {
+		//no actions while the game is paused
+		if self.is_paused() {
+			return false;
+		}
+
 		//ensure current lobby state is correct
 		if *self.lobby_state.read().await != LobbyState::BettingQBetting(false) {
 			return false;
@@ -438,7 +1246,7 @@ impl Gameshow {
 				if player.uuid == uuid {
 					//set player's money_bet
 					player.money_bet = money_bet;
-				} else if player.money_bet < 1 {
+				} else if player.is_active() && player.money_bet < 1 {
 					//check if player has bet
 					all_bet = false;
 				}
@@ -461,7 +1269,54 @@ impl Gameshow {
 		true
 	}
 
-	pub async fn attack(&self, uuid: &str, vs_player: &str) -> bool {
+	pub async fn wager(&self, uuid: &str, money_wager: i64) -> bool {
+		//no actions while the game is paused
+		if self.is_paused() {
+			return false;
+		}
+
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::FinalQWagering(false) {
+			return false;
+		}
+
+		let mut all_wagered = true;
+		{
+			//perform wagering and check if all players have wagered
+			let mut player_access = self.player_data.write().await;
+			for player in (*player_access).iter_mut() {
+				if player.uuid == uuid {
+					//set player's sealed wager
+					player.final_wager = Some(money_wager);
+				} else if player.is_active() && player.final_wager.is_none() {
+					//check if player has wagered
+					all_wagered = false;
+				}
+			}
+
+			//send PlayerListUpdate to clients (without revealing the wager)
+			let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+				player_data: make_public_player_data(&player_access),
+			});
+			self.game_events.write().await.add(event);
+		}
+
+		//indicate abilitiy to proceed when all players wagered
+		if all_wagered {
+			let mut state = self.lobby_state.write().await;
+			*state = LobbyState::FinalQWagering(true);
+		}
+
+		self.state_transition().await;
+		true
+	}
+
+	pub async fn attack(&self, uuid: &str, vs_player: usize) -> bool {
+		//no actions while the game is paused
+		if self.is_paused() {
+			return false;
+		}
+
 		//ensure current lobby state is correct
 		if *self.lobby_state.read().await != LobbyState::VersusQSelecting(false) {
 			return false;
@@ -474,8 +1329,8 @@ impl Gameshow {
 			for player in (*player_access).iter_mut() {
 				if player.uuid == uuid {
 					//set player's vs_player
-					player.vs_player = String::from(vs_player);
-				} else if player.vs_player.is_empty() {
+					player.vs_player = vs_player;
+				} else if player.is_active() && player.vs_player == 0 {
 					//check if player has selected
 					all_selected = false;
 				}
@@ -499,6 +1354,16 @@ impl Gameshow {
 	}
 
 	pub async fn answer(&self, uuid: &str, answer: usize) -> bool {
+		//no actions while the game is paused
+		if self.is_paused() {
+			return false;
+		}
+
+		//buzzer questions are answered by the buzzing player only
+		if *self.lobby_state.read().await == LobbyState::BuzzerQAnswering(false) {
+			return self.answer_buzzer(uuid, Some(answer), None).await;
+		}
+
 		//ensure current lobby state is correct
 		{
 			let state = self.lobby_state.read().await;
@@ -506,6 +1371,7 @@ impl Gameshow {
 				&& *state != LobbyState::BettingQAnswering(false)
 				&& *state != LobbyState::EstimationQAnswering(false)
 				&& *state != LobbyState::VersusQAnswering(false)
+				&& *state != LobbyState::FinalQAnswering(false)
 			{
 				return false;
 			}
@@ -519,7 +1385,7 @@ impl Gameshow {
 				if player.uuid == uuid {
 					//set player's answer
 					player.answer = answer;
-				} else if player.answer < 1 {
+				} else if player.is_active() && !player.has_answered() {
 					//check if player has answered
 					all_answered = false;
 				}
@@ -540,54 +1406,442 @@ impl Gameshow {
 				LobbyState::BettingQAnswering(_) => LobbyState::BettingQAnswering(true),
 				LobbyState::EstimationQAnswering(_) => LobbyState::EstimationQAnswering(true),
 				LobbyState::VersusQAnswering(_) => LobbyState::VersusQAnswering(true),
+				LobbyState::FinalQAnswering(_) => LobbyState::FinalQAnswering(true),
 				default => default,
 			};
 		}
 
+		//other players' answers may count for running audience polls
+		if self.get_audience_poll_includes_players() {
+			self.generate_poll_update().await;
+		}
+
 		self.state_transition().await;
 		true
 	}
 
-	pub async fn get_joker(&self, uuid: &str) -> Option<Vec<usize>> {
+	pub async fn buzz(self: &Arc<Self>, uuid: &str) -> Result<(), BuzzError> {
+		//no actions while the game is paused
+		if self.is_paused() {
+			return Err(BuzzError::Paused);
+		}
+
+		//the state lock makes the first arriving buzz win
+		let mut state = self.lobby_state.write().await;
+		if *state != LobbyState::BuzzerQBuzzing(false) {
+			return Err(BuzzError::WrongState);
+		}
+		let answer_time = self.buzzer_settings.read().await.answer_time;
+
+		let token = {
+			let mut buzzer_access = self.buzzer.write().await;
+			let player_access = self.player_data.read().await;
+			let player = match (*player_access).iter().find(|player| player.uuid == uuid) {
+				Some(player) => player,
+				None => return Err(BuzzError::PlayerNotFound),
+			};
+			if !player.is_active() {
+				return Err(BuzzError::Eliminated);
+			}
+			if buzzer_access.locked_out.iter().any(|locked_out| locked_out == uuid) {
+				return Err(BuzzError::LockedOut);
+			}
+			let timeout = Some(Duration::from_secs(answer_time)).filter(|_| answer_time > 0);
+			let token = buzzer_access.grant(uuid, timeout);
+
+			//send Buzzed to clients
+			let event = EventType::Buzzed(EventBuzzed {
+				name: player.name.clone(),
+				buzz_order: player_names(&player_access, &buzzer_access.buzz_order),
+				answer_time,
+			});
+			self.game_events.write().await.add(event);
+			token
+		};
+		*state = LobbyState::BuzzerQAnswering(false);
+
+		//the answer right runs out if the player does not answer in time
+		if answer_time > 0 {
+			self.start_buzzer_timeout(Duration::from_secs(answer_time), token);
+		}
+
+		Ok(())
+	}
+
+	fn start_buzzer_timeout(self: &Arc<Self>, answer_time: Duration, token: usize) {
+		let game = Arc::clone(self);
+		rt::spawn(async move {
+			rt::time::sleep(answer_time).await;
+			game.answer_buzzer_timeout(token).await;
+		});
+	}
+
+	async fn answer_buzzer_timeout(&self, token: usize) {
+		let uuid = {
+			let buzzer_access = self.buzzer.read().await;
+			match &buzzer_access.answering {
+				Some(uuid) if buzzer_access.answer_token == token => uuid.clone(),
+				_ => return,
+			}
+		};
+		self.answer_buzzer(&uuid, None, Some(token)).await;
+	}
+
+	//answer of the buzzing player, None if the time ran out (only for the
+	// given token then)
+	async fn answer_buzzer(&self, uuid: &str, answer: Option<usize>, token: Option<usize>) -> bool {
 		//ensure current lobby state is correct
+		let mut state = self.lobby_state.write().await;
+		if *state != LobbyState::BuzzerQAnswering(false) {
+			return false;
+		}
+		let question_id = self.current_question.load(Ordering::Relaxed);
+		let correct_answer = (*self.questions.read().await)[question_id - 1].correct_answer;
+
 		{
-			let state = self.lobby_state.read().await;
-			if *state != LobbyState::NormalQAnswering(false)
-				&& *state != LobbyState::BettingQAnswering(false)
+			let mut buzzer_access = self.buzzer.write().await;
+			if buzzer_access.answering.as_deref() != Some(uuid)
+				|| token.is_some_and(|token| token != buzzer_access.answer_token)
 			{
-				return None;
+				return false;
 			}
+			let mut player_access = self.player_data.write().await;
+			let player = match (*player_access).iter_mut().find(|player| player.uuid == uuid) {
+				Some(player) => player,
+				None => return false,
+			};
+			player.answer = answer.unwrap_or(0);
+			let name = player.name.clone();
+
+			if answer == Some(correct_answer) {
+				*state = LobbyState::BuzzerQAnswering(true);
+			} else {
+				//lock the player out and let the others buzz again if possible
+				let reopened = buzzer_access.lock_out_answering(&player_access);
+				*state = if reopened {
+					LobbyState::BuzzerQBuzzing(false)
+				} else {
+					LobbyState::BuzzerQAnswering(true)
+				};
+
+				//send BuzzerAnswerWrong to clients
+				let event = EventType::BuzzerAnswerWrong(EventBuzzerAnswerWrong {
+					name,
+					answer: answer.unwrap_or(0),
+					locked_out: player_names(&player_access, &buzzer_access.locked_out),
+					reopened,
+				});
+				self.game_events.write().await.add(event);
+			}
+
+			//send PlayerListUpdate to clients
+			let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+				player_data: make_public_player_data(&player_access),
+			});
+			self.game_events.write().await.add(event);
+		}
+
+		drop(state);
+		self.state_transition().await;
+		true
+	}
+
+	pub async fn vote_audience_poll(&self, uuid: &str, answer: usize) -> bool {
+		//ensure current lobby state is correct
+		if *self.lobby_state.read().await != LobbyState::NormalQAnswering(false) {
+			return false;
 		}
 
-		//get wrong answers
-		let wrong_answers: Vec<usize>;
 		{
-			let mut rng = rand::thread_rng();
+			//only spectators vote, players answer the question themselves
+			let player_access = self.player_data.read().await;
+			if (*player_access).iter().any(|player| player.uuid == uuid && player.is_active()) {
+				return false;
+			}
+
+			let mut poll_access = self.audience_poll.write().await;
+			match &mut *poll_access {
+				Some(poll) => {
+					poll.votes.insert(uuid.to_owned(), answer);
+				}
+				None => return false,
+			}
+		}
+
+		self.generate_poll_update().await;
+		true
+	}
+
+	pub async fn get_joker(&self, uuid: &str, joker: JokerType) -> Result<JokerResult, JokerError> {
+		//no actions while the game is paused
+		if self.is_paused() {
+			return Err(JokerError::Paused);
+		}
+
+		//ensure current lobby state is correct
+		let state = *self.lobby_state.read().await;
+		if !joker.is_usable_in(state) {
+			return Err(JokerError::WrongState);
+		}
+
+		let player_language = self
+			.player_data
+			.read()
+			.await
+			.iter()
+			.find(|p| p.uuid == uuid)
+			.map(|p| p.language.clone());
+		let player_language = player_language.ok_or(JokerError::PlayerNotFound)?;
+
+		//compute what the joker reveals
+		let result;
+		{
 			let current_question = self.current_question.load(Ordering::Relaxed);
+			let language = self.language.read().await.clone();
 			let questions_access = self.questions.read().await;
-			let correct_answer = (*questions_access)[current_question - 1].correct_answer;
-			let mut choose_from = vec![1, 2, 3, 4];
-			choose_from.remove(correct_answer - 1); //removed by index
-			wrong_answers = choose_from.choose_multiple(&mut rng, 2).copied().collect();
+			let question = &(*questions_access)[current_question - 1];
+			result = match joker {
+				JokerType::FiftyFifty => {
+					//get wrong answers
+					let mut rng = rand::thread_rng();
+					let mut choose_from = vec![1, 2, 3, 4];
+					choose_from.remove(question.correct_answer - 1); //removed by index
+					JokerResult::WrongAnswers(
+						choose_from.choose_multiple(&mut rng, 2).copied().collect(),
+					)
+				}
+				JokerType::Hint => {
+					let hint = question.hint.as_ref().ok_or(JokerError::NoHint)?;
+					JokerResult::Hint(hint.resolve(&player_language, &language))
+				}
+				JokerType::EstimationRange => {
+					let (min, max) = jokers::estimation_range(question.correct_answer);
+					JokerResult::Range { min, max }
+				}
+				JokerType::AudiencePoll => JokerResult::Poll { histogram: Vec::new() }, //see below
+				JokerType::Skip | JokerType::Double => JokerResult::Activated,
+			};
 		}
 
-		//decrement player's jokers wrong answers
-		let mut player_access = self.player_data.write().await;
-		for player in (*player_access).iter_mut() {
-			if player.uuid == uuid {
-				player.jokers -= 1;
-				break;
+		let all_answered;
+		{
+			//decrement player's jokers and remember the usage for this question
+			let mut player_access = self.player_data.write().await;
+			let player = (*player_access)
+				.iter_mut()
+				.find(|player| player.uuid == uuid)
+				.ok_or(JokerError::PlayerNotFound)?;
+			if !player.is_active() {
+				return Err(JokerError::Eliminated);
 			}
+			if player.joker_count(joker) < 1 {
+				return Err(JokerError::NoJokersLeft);
+			}
+			if player.has_used_joker(joker) {
+				return Err(JokerError::AlreadyUsed);
+			}
+			player.take_joker(joker);
+			let name = player.name.clone();
+			all_answered =
+				(*player_access).iter().all(|player| !player.is_active() || player.has_answered());
+
+			//open the poll or add the player to the running one
+			if joker == JokerType::AudiencePoll {
+				let mut poll_access = self.audience_poll.write().await;
+				poll_access
+					.get_or_insert_with(AudiencePoll::default)
+					.requesters
+					.push(uuid.to_owned());
+			}
+
+			//announce the joker and send PlayerListUpdate to clients
+			let mut events = self.game_events.write().await;
+			events.add(EventType::JokerUsed(EventJokerUsed { name, joker }));
+			events.add(EventType::PlayerListUpdate(EventPlayerListUpdate {
+				player_data: make_public_player_data(&player_access),
+			}));
 		}
 
-		//send PlayerListUpdate to clients
-		let event = EventType::PlayerListUpdate(EventPlayerListUpdate {
-			player_data: make_public_player_data(&player_access),
+		//skipping counts as answered, so indicate ability to proceed
+		if joker == JokerType::Skip && all_answered {
+			let mut state = self.lobby_state.write().await;
+			*state = match *state {
+				LobbyState::NormalQAnswering(_) => LobbyState::NormalQAnswering(true),
+				LobbyState::BettingQAnswering(_) => LobbyState::BettingQAnswering(true),
+				LobbyState::EstimationQAnswering(_) => LobbyState::EstimationQAnswering(true),
+				LobbyState::VersusQAnswering(_) => LobbyState::VersusQAnswering(true),
+				default => default,
+			};
+		}
+		self.state_transition().await;
+
+		//the poll's current state is returned, updates are sent privately
+		if joker == JokerType::AudiencePoll {
+			let histogram = self.generate_poll_update().await.unwrap_or_default();
+			return Ok(JokerResult::Poll { histogram });
+		}
+
+		//send/return the joker's result
+		Ok(result)
+	}
+
+	pub async fn select_question(&self, uuid: &str, question_id: usize) -> Result<(), BoardError> {
+		//no actions while the game is paused
+		if self.is_paused() {
+			return Err(BoardError::Paused);
+		}
+
+		//ensure current lobby state is correct
+		let mut state = self.lobby_state.write().await;
+		if *state != LobbyState::BoardSelecting(false) {
+			return Err(BoardError::WrongState);
+		}
+
+		{
+			let num_questions = self.questions.read().await.len();
+			let mut board_access = self.board.write().await;
+			if board_access.chooser.as_deref() != Some(uuid) {
+				return Err(BoardError::NotChooser);
+			}
+			if !board_access.is_available(question_id, num_questions) {
+				return Err(BoardError::InvalidQuestion);
+			}
+			board_access.selected = Some(question_id);
+		}
+
+		//transition to the selected question
+		*state = LobbyState::BoardSelecting(true);
+		drop(state);
+		self.state_transition().await;
+
+		Ok(())
+	}
+
+	pub fn is_paused(&self) -> bool {
+		self.paused.load(Ordering::Relaxed)
+	}
+
+	pub async fn pause(&self) -> bool {
+		//ensure the game is running and not paused yet
+		let state = self.lobby_state.read().await;
+		if matches!(*state, LobbyState::Menu(_)) || self.paused.swap(true, Ordering::Relaxed) {
+			return false;
+		}
+
+		//stop a running answer time
+		let answer_time_left = self.buzzer.write().await.pause();
+
+		//send Paused to clients
+		let event = EventType::Paused(EventPaused {
+			answer_time_left: answer_time_left.map(|time_left| time_left.as_secs_f64()),
 		});
 		self.game_events.write().await.add(event);
 
-		//send/return wrong answers as joker
-		Some(wrong_answers)
+		true
+	}
+
+	pub async fn resume(self: &Arc<Self>) -> bool {
+		//ensure the game is paused
+		let _state = self.lobby_state.read().await;
+		if !self.paused.swap(false, Ordering::Relaxed) {
+			return false;
+		}
+
+		//continue a stopped answer time
+		let resumed = self.buzzer.write().await.resume();
+		if let Some((answer_time_left, token)) = resumed {
+			self.start_buzzer_timeout(answer_time_left, token);
+		}
+
+		//send Resumed to clients
+		let event = EventType::Resumed(EventResumed {
+			answer_time_left: resumed.map(|(answer_time_left, _)| answer_time_left.as_secs_f64()),
+		});
+		self.game_events.write().await.add(event);
+
+		true
+	}
+
+	pub async fn judge(&self, player_id: usize, correct: bool) -> Result<(), JudgeError> {
+		{
+			//ensure current lobby state is correct
+			let state = self.lobby_state.read().await;
+			if *state != LobbyState::Judging(false) {
+				return Err(JudgeError::WrongState);
+			}
+
+			let mut judging_access = self.judging.write().await;
+			let judging = judging_access.as_mut().ok_or(JudgeError::WrongState)?;
+			let player_access = self.player_data.read().await;
+			let player = (*player_access)
+				.iter()
+				.find(|player| player.id == player_id && player.is_active())
+				.ok_or(JudgeError::PlayerNotFound)?;
+			judging.verdicts.insert(player.uuid.clone(), correct);
+		}
+
+		self.generate_judging_update().await;
+		Ok(())
+	}
+
+	pub async fn roll_back(&self) -> bool {
+		//ensure the game is running
+		let mut state = self.lobby_state.write().await;
+		if matches!(*state, LobbyState::Menu(_)) {
+			return false;
+		}
+
+		{
+			let mut board_access = self.board.write().await;
+			let mut buzzer_access = self.buzzer.write().await;
+			let mut judging_access = self.judging.write().await;
+			let mut final_reveal_access = self.final_reveal.write().await;
+			let snapshot = match self.snapshots.write().await.pop_back() {
+				Some(snapshot) => snapshot,
+				None => return false,
+			};
+			//pending judgements belong to the discarded question
+			*judging_access = None;
+			let mut player_access = self.player_data.write().await;
+
+			//restore the game before the last scoring, keeping players that
+			// joined or left since then and names changed since then
+			let mut player_data: Vec<PlayerData> = snapshot
+				.player_data
+				.into_iter()
+				.filter_map(|mut old| {
+					let player = (*player_access).iter().find(|player| player.uuid == old.uuid)?;
+					old.name = player.name.clone();
+					Some(old)
+				})
+				.collect();
+			for player in (*player_access).iter() {
+				if !player_data.iter().any(|old| old.uuid == player.uuid) {
+					player_data.push(player.clone());
+				}
+			}
+			*player_access = player_data;
+			*state = snapshot.lobby_state;
+			self.current_question.store(snapshot.current_question, Ordering::Relaxed);
+			*board_access = snapshot.board;
+			buzzer_access.reset();
+			*final_reveal_access = FinalReveal::default();
+			*self.audience_poll.write().await = None;
+
+			//send GameRolledBack to clients
+			let event = EventType::GameRolledBack(EventGameRolledBack {
+				lobby_state: snapshot.lobby_state,
+				current_question: snapshot.current_question,
+				player_data: make_public_player_data(&player_access),
+			});
+			self.game_events.write().await.add(event);
+		}
+
+		true
+	}
+
+	pub async fn navigate(&self, navigation: Navigation) -> bool {
+		state::navigate(self, navigation).await
 	}
 
 	pub async fn next_state(&self) {
@@ -600,6 +1854,11 @@ impl Gameshow {
 		while repeat {
 			repeat = !state::state_transition(self).await;
 		}
+
+		//the admin judges the answers of the question, if required
+		if *self.lobby_state.read().await == LobbyState::Judging(false) {
+			self.generate_judging_update().await;
+		}
 	}
 }
 
@@ -607,42 +1866,128 @@ impl Gameshow {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PlayerData {
 	uuid: String,
+	id: usize, //public ID to target the player with, unlike the secret UUID
 	name: String,
-	jokers: usize,
+	jokers: usize, //50/50 jokers
+	special_jokers: BTreeMap<JokerType, usize>,
 	money: i64,
+	language: String,
 	//could also use Option<>, but easier for frontend to handle without
 	money_bet: i64,
-	vs_player: String,
+	vs_player: usize, //ID of the attacked player
 	answer: usize,
+	used_jokers: Vec<JokerType>,  //jokers used for the current question
+	eliminated: bool,             //eliminated players only spectate the rest of the game
+	round_start_money: i64,       //money at the beginning of the current round
+	round_correct_answers: usize, //correct answers in the current round
+	final_wager: Option<i64>,     //sealed wager for the final question
+}
+
+impl PlayerData {
+	fn joker_count(&self, joker: JokerType) -> usize {
+		match joker {
+			JokerType::FiftyFifty => self.jokers,
+			_ => self.special_jokers.get(&joker).copied().unwrap_or(0),
+		}
+	}
+
+	fn take_joker(&mut self, joker: JokerType) {
+		match joker {
+			JokerType::FiftyFifty => self.jokers -= 1,
+			_ => *self.special_jokers.entry(joker).or_insert(1) -= 1,
+		}
+		self.used_jokers.push(joker);
+	}
+
+	fn has_used_joker(&self, joker: JokerType) -> bool {
+		self.used_jokers.contains(&joker)
+	}
+
+	//factor for the payout of correct answers
+	fn payout_factor(&self) -> i64 {
+		if self.has_used_joker(JokerType::Double) {
+			2
+		} else {
+			1
+		}
+	}
+
+	//skipping the question counts as answering
+	fn has_answered(&self) -> bool {
+		self.answer > 0 || self.has_used_joker(JokerType::Skip)
+	}
+
+	//eliminated players are not waited for and do not get results anymore
+	fn is_active(&self) -> bool {
+		!self.eliminated
+	}
+}
+
+//summary of a lobby in the public lobby list
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LobbyListing {
+	pub admin: String, //name of the admin
+	pub players: usize,
+	pub max_players: usize, //0 for no limit
+	pub open: bool,
+	pub password_protected: bool,
+	pub phase: LobbyPhase,
+	pub paused: bool,
+	pub current_question: usize,
+	pub num_questions: usize,
+	pub question_set: String,
+	pub question_set_revision: Option<usize>,
+	pub language: String,
 }
 
 //struct for player data to be sent to clients (without uuid)
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PublicPlayerData {
+	id: usize,
 	name: String,
 	jokers: usize,
+	special_jokers: BTreeMap<JokerType, usize>,
 	money: i64,
+	language: String,
 	//could also use Option<>, but easier for frontend to handle without
 	money_bet: i64,
-	vs_player: String,
+	vs_player: usize,
 	answer: usize,
+	used_jokers: Vec<JokerType>,
+	eliminated: bool,
+	has_wagered: bool, //the final wager itself stays secret until the reveal
 }
 
 fn make_public_player_data(players: &[PlayerData]) -> Vec<PublicPlayerData> {
 	players
 		.iter()
 		.map(|player| PublicPlayerData {
+			id: player.id,
 			name: player.name.clone(),
 			jokers: player.jokers,
+			special_jokers: player.special_jokers.clone(),
 			money: player.money,
+			language: player.language.clone(),
 
 			money_bet: player.money_bet,
-			vs_player: player.vs_player.clone(),
+			vs_player: player.vs_player,
 			answer: player.answer,
+			used_jokers: player.used_jokers.clone(),
+			eliminated: player.eliminated,
+			has_wagered: player.final_wager.is_some(),
 		})
 		.collect()
 }
 
+//names of the players with the given UUIDs, keeping their order
+fn player_names(players: &[PlayerData], uuids: &[String]) -> Vec<String> {
+	uuids
+		.iter()
+		.filter_map(|uuid| players.iter().find(|player| player.uuid == *uuid))
+		.map(|player| player.name.clone())
+		.collect()
+}
+
 fn generate_random_string(length: usize) -> String {
 	let characters = "ABCDEFGHIJKLMNOPQRSTUVWXYZ\
                     abcdefghijklmnopqrstuvwxyz\
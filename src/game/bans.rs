@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+//ban of a player from a lobby, stored by the player's UUID
+pub struct Ban {
+	pub player_id: usize,         //public ID of the player when banned
+	pub name: String,             //name of the player when banned
+	pub expires: Option<Instant>, //permanent if none
+}
+
+//ban as shown to the admin
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BanInfo {
+	pub player_id: usize,
+	pub name: String,
+	pub expires_in: Option<u64>, //seconds until the ban expires, permanent if none
+}
+
+impl Ban {
+	//a duration too long to represent makes the ban permanent
+	pub fn new(player_id: usize, name: String, duration: Option<Duration>) -> Self {
+		let expires = duration.and_then(|duration| Instant::now().checked_add(duration));
+		Ban { player_id, name, expires }
+	}
+
+	pub fn is_active(&self) -> bool {
+		self.expires.is_none_or(|expires| expires > Instant::now())
+	}
+
+	pub fn info(&self) -> BanInfo {
+		BanInfo {
+			player_id: self.player_id,
+			name: self.name.clone(),
+			expires_in: self
+				.expires
+				.map(|expires| expires.saturating_duration_since(Instant::now()).as_secs()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn permanent_bans() {
+		for duration in [None, Some(Duration::MAX)] {
+			let ban = Ban::new(1, String::from("Pia"), duration);
+			assert!(ban.is_active());
+			assert!(ban.info().expires_in.is_none());
+		}
+	}
+
+	#[test]
+	fn temporary_bans_expire() {
+		let ban = Ban::new(1, String::from("Pia"), Some(Duration::from_secs(60)));
+		assert!(ban.is_active());
+		let info = ban.info();
+		assert_eq!((info.player_id, info.name.as_str()), (1, "Pia"));
+		assert!(matches!(info.expires_in, Some(59..=60)));
+
+		let ban = Ban::new(1, String::from("Pia"), Some(Duration::ZERO));
+		assert!(!ban.is_active());
+		assert_eq!(ban.info().expires_in, Some(0));
+	}
+}
@@ -0,0 +1,240 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+	questions::{Question, QuestionType},
+	reconnect, PlayerData,
+};
+
+//how the next question is determined
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum GameMode {
+	Sequential,          //questions are played in the order of the question set
+	Board(BoardChooser), //a player picks the next question from a category/value grid
+}
+
+//who picks the next question in board mode
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum BoardChooser {
+	Rotating,    //players take turns
+	LastCorrect, //the player who answered the last question correctly
+}
+
+//progress of a board game
+#[derive(Default, Clone)]
+pub struct BoardState {
+	pub played: Vec<usize>,      //IDs of questions that were already picked
+	pub chooser: Option<String>, //UUID of the player picking the next question
+	pub selected: Option<usize>, //ID of the question picked to be played next
+}
+
+//grid of the question set sent to clients, one column per category
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BoardCategory {
+	pub name: String,
+	pub cells: Vec<BoardCell>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BoardCell {
+	pub question: usize, //question ID to select the cell with
+	pub value: Option<i64>,
+	pub played: bool,
+}
+
+//reasons a question can not be selected
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BoardError {
+	WrongState,
+	NotChooser,
+	InvalidQuestion,
+	Paused,
+}
+
+impl fmt::Display for BoardError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			BoardError::WrongState => write!(f, "Game lobby is in wrong state!"),
+			BoardError::NotChooser => write!(f, "It is not your turn to choose a question!"),
+			BoardError::InvalidQuestion => {
+				write!(f, "Question does not exist or was already played!")
+			}
+			BoardError::Paused => write!(f, "Game is paused!"),
+		}
+	}
+}
+
+impl std::error::Error for BoardError {}
+
+impl BoardState {
+	//whether the question can still be picked
+	pub fn is_available(&self, question_id: usize, num_questions: usize) -> bool {
+		question_id >= 1 && question_id <= num_questions && !self.played.contains(&question_id)
+	}
+
+	//move the player to a new UUID
+	pub fn rebind(&mut self, old: &str, new: &str) {
+		if let Some(chooser) = self.chooser.as_mut() {
+			reconnect::rebind(chooser, old, new);
+		}
+	}
+
+	//first question that was not played yet
+	pub fn first_available(&self, num_questions: usize) -> Option<usize> {
+		(1..=num_questions).find(|question_id| !self.played.contains(question_id))
+	}
+
+	//determine the player picking the next question
+	pub fn next_chooser(
+		&mut self,
+		chooser: BoardChooser,
+		players: &[PlayerData],
+		correct_players: &[String],
+	) {
+		let active: Vec<&String> =
+			players.iter().filter(|player| player.is_active()).map(|player| &player.uuid).collect();
+		let current = self.chooser.as_ref().and_then(|uuid| active.iter().position(|p| *p == uuid));
+		self.chooser = match chooser {
+			BoardChooser::Rotating => match current {
+				Some(index) => active.get((index + 1) % active.len()).map(|uuid| (*uuid).clone()),
+				None => active.first().map(|uuid| (*uuid).clone()),
+			},
+			BoardChooser::LastCorrect => {
+				//a correct chooser keeps choosing, otherwise a correct player takes over
+				let keep = current.is_some()
+					&& self.chooser.as_ref().is_some_and(|uuid| correct_players.contains(uuid));
+				let correct = correct_players.iter().find(|uuid| active.contains(uuid));
+				match (keep, correct, current) {
+					(true, _, _) | (false, None, Some(_)) => self.chooser.clone(),
+					(false, Some(uuid), _) => Some(uuid.clone()),
+					(false, None, None) => active.first().map(|uuid| (*uuid).clone()),
+				}
+			}
+		};
+	}
+}
+
+//lay out the questions as grid, categories in order of appearance and cells
+// sorted by value
+pub fn make_board(
+	questions: &[Question],
+	board_state: &BoardState,
+	language: &str,
+) -> Vec<BoardCategory> {
+	let mut board: Vec<BoardCategory> = Vec::new();
+	for (i, question) in questions.iter().enumerate() {
+		let name = question.category(language, language);
+		let cell = BoardCell {
+			question: i + 1,
+			value: question.value,
+			played: board_state.played.contains(&(i + 1)),
+		};
+		match board.iter_mut().find(|category| category.name == name) {
+			Some(category) => category.cells.push(cell),
+			None => board.push(BoardCategory { name, cells: vec![cell] }),
+		}
+	}
+	for category in board.iter_mut() {
+		category.cells.sort_by_key(|cell| (cell.value, cell.question));
+	}
+	board
+}
+
+//UUIDs of the players that answered the question correctly (the closest
+// ones for estimation questions)
+pub fn correct_players(question: &Question, players: &[PlayerData]) -> Vec<String> {
+	let answered = players.iter().filter(|player| player.is_active() && player.answer > 0);
+	if question.question_type == QuestionType::EstimationQuestion {
+		let answered: Vec<&PlayerData> = answered.collect();
+		let min_distance =
+			answered.iter().map(|player| player.answer.abs_diff(question.correct_answer)).min();
+		answered
+			.into_iter()
+			.filter(|player| Some(player.answer.abs_diff(question.correct_answer)) == min_distance)
+			.map(|player| player.uuid.clone())
+			.collect()
+	} else {
+		answered
+			.filter(|player| player.answer == question.correct_answer)
+			.map(|player| player.uuid.clone())
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+
+	use super::*;
+
+	fn player(uuid: &str, eliminated: bool) -> PlayerData {
+		PlayerData {
+			uuid: String::from(uuid),
+			id: 0,
+			name: String::from(uuid),
+			jokers: 0,
+			special_jokers: BTreeMap::new(),
+			money: 0,
+			language: String::from("en"),
+			money_bet: 0,
+			vs_player: 0,
+			answer: 0,
+			used_jokers: Vec::new(),
+			eliminated,
+			round_start_money: 0,
+			round_correct_answers: 0,
+			final_wager: None,
+		}
+	}
+
+	fn players() -> Vec<PlayerData> {
+		vec![player("a", false), player("b", true), player("c", false), player("d", false)]
+	}
+
+	fn chooser_after(
+		chooser: Option<&str>,
+		mode: BoardChooser,
+		correct_players: &[&str],
+	) -> Option<String> {
+		let mut board_state =
+			BoardState { chooser: chooser.map(String::from), ..BoardState::default() };
+		let correct_players: Vec<String> =
+			correct_players.iter().map(|uuid| String::from(*uuid)).collect();
+		board_state.next_chooser(mode, &players(), &correct_players);
+		board_state.chooser
+	}
+
+	#[test]
+	fn rotating_chooser() {
+		let rotating = BoardChooser::Rotating;
+		assert_eq!(chooser_after(None, rotating, &[]).as_deref(), Some("a"));
+		//eliminated players are skipped, the last player is followed by the first
+		assert_eq!(chooser_after(Some("a"), rotating, &["a"]).as_deref(), Some("c"));
+		assert_eq!(chooser_after(Some("d"), rotating, &[]).as_deref(), Some("a"));
+		//a chooser that left or is out is replaced by the first player
+		assert_eq!(chooser_after(Some("b"), rotating, &[]).as_deref(), Some("a"));
+		assert_eq!(chooser_after(Some("x"), rotating, &[]).as_deref(), Some("a"));
+	}
+
+	#[test]
+	fn last_correct_chooser() {
+		let last_correct = BoardChooser::LastCorrect;
+		assert_eq!(chooser_after(Some("a"), last_correct, &["c", "a"]).as_deref(), Some("a"));
+		assert_eq!(chooser_after(Some("a"), last_correct, &["d", "c"]).as_deref(), Some("d"));
+		assert_eq!(chooser_after(Some("a"), last_correct, &[]).as_deref(), Some("a"));
+		//eliminated players never choose
+		assert_eq!(chooser_after(Some("a"), last_correct, &["b", "c"]).as_deref(), Some("c"));
+		assert_eq!(chooser_after(Some("b"), last_correct, &["b"]).as_deref(), Some("a"));
+		assert_eq!(chooser_after(None, last_correct, &[]).as_deref(), Some("a"));
+	}
+
+	#[test]
+	fn no_chooser_without_players() {
+		let mut board_state = BoardState::default();
+		board_state.next_chooser(BoardChooser::Rotating, &[], &[]);
+		assert!(board_state.chooser.is_none());
+		board_state.next_chooser(BoardChooser::LastCorrect, &[], &[]);
+		assert!(board_state.chooser.is_none());
+	}
+}
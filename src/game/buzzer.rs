@@ -0,0 +1,124 @@
+use std::{
+	fmt,
+	time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{reconnect, PlayerData};
+
+//standard parameters for buzzer questions
+const ANSWER_TIME: u64 = 10; //seconds the first buzzing player has to answer
+const MAX_ANSWER_TIME: u64 = 60 * 60; //seconds, longer answer times are rejected
+
+//buzzer settings of a lobby, normal questions are buzzer questions if enabled
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct BuzzerSettings {
+	pub enabled: bool,
+	pub answer_time: u64,   //seconds to answer after buzzing, 0 for no limit
+	pub wrong_penalty: i64, //money lost for a wrong (or timed out) answer
+}
+
+impl Default for BuzzerSettings {
+	fn default() -> Self {
+		BuzzerSettings { enabled: false, answer_time: ANSWER_TIME, wrong_penalty: 0 }
+	}
+}
+
+impl BuzzerSettings {
+	//whether the settings can be used for a game
+	pub fn is_valid(&self) -> bool {
+		self.answer_time <= MAX_ANSWER_TIME && self.wrong_penalty >= 0
+	}
+}
+
+//progress of the current buzzer question
+#[derive(Default)]
+pub struct BuzzerState {
+	pub buzz_order: Vec<String>, //UUIDs of the players that buzzed, in arrival order
+	pub locked_out: Vec<String>, //UUIDs of the players that answered wrong
+	pub answering: Option<String>, //UUID of the player with the answer right
+	pub answer_token: usize,     //identifies the answer right the running timeout belongs to
+	pub deadline: Option<Instant>, //when the answer right runs out, if the time is running
+	pub remaining: Option<Duration>, //time left for the answer right while the game is paused
+}
+
+//reasons a player can not buzz
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BuzzError {
+	WrongState,
+	PlayerNotFound,
+	Eliminated,
+	LockedOut,
+	Paused,
+}
+
+impl fmt::Display for BuzzError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			BuzzError::WrongState => write!(f, "Game lobby is in wrong state!"),
+			BuzzError::PlayerNotFound => write!(f, "Player(you) not found"),
+			BuzzError::Eliminated => write!(f, "You are eliminated!"),
+			BuzzError::LockedOut => write!(f, "You already answered this question wrong!"),
+			BuzzError::Paused => write!(f, "Game is paused!"),
+		}
+	}
+}
+
+impl std::error::Error for BuzzError {}
+
+impl BuzzerState {
+	//give the answer right to the player, returns the token for its timeout
+	pub fn grant(&mut self, uuid: &str, answer_time: Option<Duration>) -> usize {
+		self.buzz_order.push(uuid.to_owned());
+		self.answering = Some(uuid.to_owned());
+		self.deadline = answer_time.and_then(|answer_time| Instant::now().checked_add(answer_time));
+		self.answer_token += 1;
+		self.answer_token
+	}
+
+	//stop the running timeout, returns the time left
+	pub fn pause(&mut self) -> Option<Duration> {
+		let deadline = self.deadline.take()?;
+		self.remaining = Some(deadline.saturating_duration_since(Instant::now()));
+		self.answer_token += 1;
+		self.remaining
+	}
+
+	//continue the stopped timeout, returns the time left and the new token
+	pub fn resume(&mut self) -> Option<(Duration, usize)> {
+		let remaining = self.remaining.take()?;
+		self.deadline = Some(Instant::now() + remaining);
+		self.answer_token += 1;
+		Some((remaining, self.answer_token))
+	}
+
+	//lock the answering player out, returns whether other players can still buzz
+	pub fn lock_out_answering(&mut self, players: &[PlayerData]) -> bool {
+		if let Some(uuid) = self.answering.take() {
+			self.locked_out.push(uuid);
+		}
+		self.deadline = None;
+		players.iter().any(|player| player.is_active() && !self.locked_out.contains(&player.uuid))
+	}
+
+	//move the player to a new UUID
+	pub fn rebind(&mut self, old: &str, new: &str) {
+		let uuids = self.buzz_order.iter_mut().chain(self.locked_out.iter_mut());
+		for uuid in uuids.chain(self.answering.iter_mut()) {
+			reconnect::rebind(uuid, old, new);
+		}
+	}
+
+	//reset for the next question, keeping the token sequence so old timeouts
+	// stay invalid
+	pub fn reset(&mut self) {
+		self.buzz_order.clear();
+		self.locked_out.clear();
+		self.answering = None;
+		self.deadline = None;
+		self.remaining = None;
+		self.answer_token += 1;
+	}
+}
@@ -1,20 +1,110 @@
-use std::fmt;
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+};
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
-use super::{questions::*, PublicPlayerData};
+use super::{
+	board::{BoardCategory, GameMode},
+	buzzer::BuzzerSettings,
+	jokers::JokerType,
+	questions::*,
+	ruleset::Ruleset,
+	state::{LobbyState, Navigation},
+	PublicPlayerData,
+};
 
-//event manager
+//event manager; private events are only sent to one player (by UUID) and
+// have their own id sequence per player
 pub struct EventManager {
 	events: Vec<Event>,
 	event_sender: broadcast::Sender<Event>,
+	private_events: HashMap<String, Vec<Event>>,
+	private_event_sender: broadcast::Sender<(String, Event)>,
+	listing: Option<ListingNotifier>,
+}
+
+//tells the server-wide lobby list which listed lobby changed, by lobby UUID
+struct ListingNotifier {
+	lobby_id: String,
+	sender: broadcast::Sender<String>,
+	listed: bool,
+	players: usize, //number of players in the last player list update
+}
+
+impl ListingNotifier {
+	fn notify(&self) {
+		self.sender.send(self.lobby_id.clone()).ok();
+	}
+
+	//whether the event changes what the lobby list shows: players, settings,
+	// phase, pause or question progress; answers and scores do not
+	fn is_relevant(&mut self, event: &EventType) -> bool {
+		match event {
+			EventType::PlayerListUpdate(update) => {
+				let players = update.player_data.len();
+				let changed = players != self.players;
+				self.players = players;
+				changed
+			}
+			EventType::LobbySettingsUpdate(_)
+			| EventType::Paused(_)
+			| EventType::Resumed(_)
+			| EventType::BeginNormalQAnswering(_)
+			| EventType::BeginBettingQBetting(_)
+			| EventType::BeginEstimationQAnswering(_)
+			| EventType::BeginVersusQSelecting(_)
+			| EventType::BeginFinalQWagering(_)
+			| EventType::BeginBuzzerQBuzzing(_)
+			| EventType::BoardUpdate(_)
+			| EventType::RoundIntro(_)
+			| EventType::QuestionNavigation(_)
+			| EventType::GameRolledBack(_)
+			| EventType::GameEnding(_)
+			| EventType::BackToMenu(_) => true,
+			_ => false,
+		}
+	}
 }
 
 impl EventManager {
 	pub fn new() -> Self {
 		let (sender, _receiver) = broadcast::channel(50);
-		EventManager { events: Vec::new(), event_sender: sender }
+		let (private_sender, _private_receiver) = broadcast::channel(50);
+		EventManager {
+			events: Vec::new(),
+			event_sender: sender,
+			private_events: HashMap::new(),
+			private_event_sender: private_sender,
+			listing: None,
+		}
+	}
+
+	pub fn set_listing_updates(&mut self, lobby_id: String, sender: broadcast::Sender<String>) {
+		self.listing = Some(ListingNotifier { lobby_id, sender, listed: false, players: 0 });
+	}
+
+	//relevant public events of listed lobbies are announced to the lobby list;
+	// unlisting is announced once so the lobby can be removed from it
+	pub fn set_listed(&mut self, listed: bool) -> &mut Self {
+		if let Some(listing) = &mut self.listing {
+			if listing.listed && !listed {
+				listing.notify();
+			}
+			listing.listed = listed;
+		}
+		self
+	}
+
+	//announce a change of the lobby to the lobby list that comes without a
+	// relevant event, like the admin's name
+	pub fn notify_listing(&mut self) -> &mut Self {
+		if let Some(listing) = self.listing.as_ref().filter(|listing| listing.listed) {
+			listing.notify();
+		}
+		self
 	}
 
 	pub fn get(&self) -> Vec<Event> {
@@ -28,6 +118,12 @@ impl EventManager {
 			id = last.id + 1;
 		}
 		let event_type = format!("{}", event);
+		//the player count is followed while unlisted too, to be current once listed
+		if let Some(listing) = &mut self.listing {
+			if listing.is_relevant(&event) && listing.listed {
+				listing.notify();
+			}
+		}
 
 		let new_event = Event { id, event_name: event_type, event };
 		self.events.push(new_event.clone());
@@ -43,6 +139,34 @@ impl EventManager {
 	pub fn get_subscribers(&self) -> usize {
 		self.event_sender.receiver_count()
 	}
+
+	pub fn get_private(&self, uuid: &str) -> Vec<Event> {
+		self.private_events.get(uuid).cloned().unwrap_or_default()
+	}
+
+	//hand the private events over to the player's new UUID
+	pub fn rebind_private(&mut self, old: &str, new: &str) -> &mut Self {
+		if let Some(events) = self.private_events.remove(old) {
+			self.private_events.insert(new.to_owned(), events);
+		}
+		self
+	}
+
+	pub fn add_private(&mut self, uuid: &str, event: EventType) -> &mut Self {
+		let events = self.private_events.entry(uuid.to_owned()).or_default();
+		let id = events.last().map(|last| last.id + 1).unwrap_or(0);
+		let event_type = format!("{}", event);
+
+		let new_event = Event { id, event_name: event_type, event };
+		events.push(new_event.clone());
+		self.private_event_sender.send((uuid.to_owned(), new_event)).ok();
+
+		self
+	}
+
+	pub fn subscribe_private(&self) -> broadcast::Receiver<(String, Event)> {
+		self.private_event_sender.subscribe()
+	}
 }
 
 //structs for events
@@ -53,17 +177,42 @@ pub struct EventBeginNormalQAnswering {
 	pub category: String,
 	pub question: String,
 	pub answers: Vec<String>,
+	pub translations: BTreeMap<String, QuestionTranslation>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventBeginBuzzerQBuzzing {
+	pub question_type: QuestionType,
+	pub current_question: usize,
+	pub category: String,
+	pub question: String,
+	pub answers: Vec<String>,
+	pub translations: BTreeMap<String, QuestionTranslation>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventBuzzed {
+	pub name: String,            //name of the player with the answer right
+	pub buzz_order: Vec<String>, //names of all players that buzzed, in arrival order
+	pub answer_time: u64,        //seconds to answer, 0 for no limit
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventBuzzerAnswerWrong {
+	pub name: String,
+	pub answer: usize,           //0 if the time ran out
+	pub locked_out: Vec<String>, //names of the players that can not buzz anymore
+	pub reopened: bool,          //whether the remaining players can buzz again
 }
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EventBeginBettingQBetting {
 	pub question_type: QuestionType,
 	pub current_question: usize,
 	pub category: String,
+	pub translations: BTreeMap<String, QuestionTranslation>,
 }
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EventBeginBettingQAnswering {
 	pub question: String,
 	pub answers: Vec<String>,
+	pub translations: BTreeMap<String, QuestionTranslation>,
 }
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EventBeginEstimationQAnswering {
@@ -71,17 +220,20 @@ pub struct EventBeginEstimationQAnswering {
 	pub current_question: usize,
 	pub category: String,
 	pub question: String,
+	pub translations: BTreeMap<String, QuestionTranslation>,
 }
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EventBeginVersusQSelecting {
 	pub question_type: QuestionType,
 	pub current_question: usize,
 	pub category: String,
+	pub translations: BTreeMap<String, QuestionTranslation>,
 }
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EventBeginVersusQAnswering {
 	pub question: String,
 	pub answers: Vec<String>,
+	pub translations: BTreeMap<String, QuestionTranslation>,
 }
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EventShowResults {
@@ -104,12 +256,145 @@ pub struct EventPlayerListUpdate {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct EventLobbySettingsUpdate {
 	pub open: bool,
+	pub listed: bool,
+	pub password_protected: bool,
+	pub max_players: usize,
+	pub approval_required: bool,
+	pub lock_renames: bool,
 	pub initial_money: i64,
 	pub initial_jokers: usize,
 	pub normal_q_money: i64,
 	pub estimation_q_money: i64,
 	pub question_set: String,
+	pub question_set_revision: Option<usize>,
+	pub language: String,
+	pub special_jokers: BTreeMap<JokerType, usize>,
+	pub audience_poll_includes_players: bool,
+	pub ruleset: Ruleset,
+	pub game_mode: GameMode,
+	pub buzzer: BuzzerSettings,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventJokerUsed {
+	pub name: String,
+	pub joker: JokerType,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventAudiencePollUpdate {
+	pub histogram: Vec<usize>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventPlayerEliminated {
+	pub eliminated: Vec<String>, //names of the newly eliminated players
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventRoundIntro {
+	pub round: usize, //starting at 1
+	pub num_rounds: usize,
+	pub name: String,
+	pub intro: Option<String>,
+	pub multiplier: i64,
+	pub num_questions: usize,
+	pub translations: BTreeMap<String, RoundTranslation>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoundPlayerStats {
+	pub name: String,
+	pub money_change: i64,
+	pub correct_answers: usize,
 }
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventRoundSummary {
+	pub round: usize, //starting at 1
+	pub num_rounds: usize,
+	pub name: String,
+	pub translations: BTreeMap<String, RoundTranslation>,
+	pub standings: Vec<PublicPlayerData>, //sorted by money, best first
+	pub stats: Vec<RoundPlayerStats>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventBeginFinalQWagering {
+	pub question_type: QuestionType,
+	pub current_question: usize,
+	pub category: String,
+	pub translations: BTreeMap<String, QuestionTranslation>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventBeginFinalQAnswering {
+	pub question: String,
+	pub answers: Vec<String>,
+	pub translations: BTreeMap<String, QuestionTranslation>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventBeginFinalQReveal {
+	pub num_players: usize, //number of players that are revealed one by one
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventFinalQRevealPlayer {
+	pub name: String,
+	pub answer: usize,
+	pub correct: bool,
+	pub wager: i64,
+	pub previous_money: i64,
+	pub money: i64,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventBoardUpdate {
+	pub board: Vec<BoardCategory>,
+	pub chooser: Option<String>, //name of the player picking the next question
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventGameRolledBack {
+	pub lobby_state: LobbyState, //state the question continues in
+	pub current_question: usize,
+	pub player_data: Vec<PublicPlayerData>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventQuestionNavigation {
+	pub navigation: Navigation,
+	pub from_question: usize,
+	pub to_question: usize, //past the last question if the game ends, 0 if picked from the board
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventPaused {
+	pub answer_time_left: Option<f64>, //seconds left for a buzzer answer, if running
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventResumed {
+	pub answer_time_left: Option<f64>, //seconds left for a buzzer answer, if running
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventBeginJudging {
+	pub current_question: usize,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JudgedAnswer {
+	pub id: usize,
+	pub name: String,
+	pub answer: usize,
+	pub correct: bool, //host's verdict, or whether the answer is correct if not judged yet
+	pub judged: bool,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventJudgingUpdate {
+	pub answers: Vec<JudgedAnswer>,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct JoinRequestInfo {
+	pub id: usize, //ID the player gets when accepted
+	pub name: String,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventJoinRequestsUpdate {
+	pub requests: Vec<JoinRequestInfo>, //players waiting to be accepted
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventJoinRequestAnswered {
+	pub accepted: bool,
+	pub name: String,
+}
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventSessionMoved {}
 //combining struct for events
 #[derive(Serialize, Deserialize, Clone)]
 pub enum EventType {
@@ -124,6 +409,28 @@ pub enum EventType {
 	BackToMenu(EventBackToMenu),
 	PlayerListUpdate(EventPlayerListUpdate),
 	LobbySettingsUpdate(EventLobbySettingsUpdate),
+	JokerUsed(EventJokerUsed),
+	AudiencePollUpdate(EventAudiencePollUpdate),
+	PlayerEliminated(EventPlayerEliminated),
+	RoundIntro(EventRoundIntro),
+	RoundSummary(EventRoundSummary),
+	BeginFinalQWagering(EventBeginFinalQWagering),
+	BeginFinalQAnswering(EventBeginFinalQAnswering),
+	BeginFinalQReveal(EventBeginFinalQReveal),
+	FinalQRevealPlayer(EventFinalQRevealPlayer),
+	BoardUpdate(EventBoardUpdate),
+	BeginBuzzerQBuzzing(EventBeginBuzzerQBuzzing),
+	Buzzed(EventBuzzed),
+	BuzzerAnswerWrong(EventBuzzerAnswerWrong),
+	GameRolledBack(EventGameRolledBack),
+	QuestionNavigation(EventQuestionNavigation),
+	Paused(EventPaused),
+	Resumed(EventResumed),
+	BeginJudging(EventBeginJudging),
+	JudgingUpdate(EventJudgingUpdate),
+	JoinRequestsUpdate(EventJoinRequestsUpdate),
+	JoinRequestAnswered(EventJoinRequestAnswered),
+	SessionMoved(EventSessionMoved),
 }
 
 impl fmt::Display for EventType {
@@ -140,6 +447,28 @@ impl fmt::Display for EventType {
 			EventType::BackToMenu(_) => write!(f, "BackToMenu"),
 			EventType::PlayerListUpdate(_) => write!(f, "PlayerListUpdate"),
 			EventType::LobbySettingsUpdate(_) => write!(f, "LobbySettingsUpdate"),
+			EventType::JokerUsed(_) => write!(f, "JokerUsed"),
+			EventType::AudiencePollUpdate(_) => write!(f, "AudiencePollUpdate"),
+			EventType::PlayerEliminated(_) => write!(f, "PlayerEliminated"),
+			EventType::RoundIntro(_) => write!(f, "RoundIntro"),
+			EventType::RoundSummary(_) => write!(f, "RoundSummary"),
+			EventType::BeginFinalQWagering(_) => write!(f, "BeginFinalQWagering"),
+			EventType::BeginFinalQAnswering(_) => write!(f, "BeginFinalQAnswering"),
+			EventType::BeginFinalQReveal(_) => write!(f, "BeginFinalQReveal"),
+			EventType::FinalQRevealPlayer(_) => write!(f, "FinalQRevealPlayer"),
+			EventType::BoardUpdate(_) => write!(f, "BoardUpdate"),
+			EventType::BeginBuzzerQBuzzing(_) => write!(f, "BeginBuzzerQBuzzing"),
+			EventType::Buzzed(_) => write!(f, "Buzzed"),
+			EventType::BuzzerAnswerWrong(_) => write!(f, "BuzzerAnswerWrong"),
+			EventType::GameRolledBack(_) => write!(f, "GameRolledBack"),
+			EventType::QuestionNavigation(_) => write!(f, "QuestionNavigation"),
+			EventType::Paused(_) => write!(f, "Paused"),
+			EventType::Resumed(_) => write!(f, "Resumed"),
+			EventType::BeginJudging(_) => write!(f, "BeginJudging"),
+			EventType::JudgingUpdate(_) => write!(f, "JudgingUpdate"),
+			EventType::JoinRequestsUpdate(_) => write!(f, "JoinRequestsUpdate"),
+			EventType::JoinRequestAnswered(_) => write!(f, "JoinRequestAnswered"),
+			EventType::SessionMoved(_) => write!(f, "SessionMoved"),
 		}
 	}
 }
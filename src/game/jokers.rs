@@ -0,0 +1,149 @@
+use std::{collections::HashMap, fmt};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::state::LobbyState;
+
+//different jokers players can use while answering
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum JokerType {
+	FiftyFifty,      //remove two wrong answers
+	Skip,            //do not answer, the question has no effect on the player
+	Double,          //double the payout if answered correctly
+	Hint,            //reveal the question's hint
+	EstimationRange, //reveal a range containing the correct estimation
+	AudiencePoll,    //ask spectators (and optionally other players) for their answer
+}
+
+impl JokerType {
+	//whether the joker can be used in the given lobby state
+	pub fn is_usable_in(&self, state: LobbyState) -> bool {
+		match self {
+			JokerType::FiftyFifty => matches!(
+				state,
+				LobbyState::NormalQAnswering(false) | LobbyState::BettingQAnswering(false)
+			),
+			JokerType::Skip | JokerType::Hint => matches!(
+				state,
+				LobbyState::NormalQAnswering(false)
+					| LobbyState::BettingQAnswering(false)
+					| LobbyState::EstimationQAnswering(false)
+					| LobbyState::VersusQAnswering(false)
+			),
+			JokerType::Double => matches!(
+				state,
+				LobbyState::NormalQAnswering(false)
+					| LobbyState::BettingQAnswering(false)
+					| LobbyState::EstimationQAnswering(false)
+			),
+			JokerType::EstimationRange => state == LobbyState::EstimationQAnswering(false),
+			JokerType::AudiencePoll => state == LobbyState::NormalQAnswering(false),
+		}
+	}
+}
+
+//what a player gets for using a joker
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum JokerResult {
+	WrongAnswers(Vec<usize>),
+	Hint(String),
+	Range { min: usize, max: usize },
+	Poll { histogram: Vec<usize> },
+	Activated,
+}
+
+//reasons a joker can not be used
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum JokerError {
+	WrongState,
+	PlayerNotFound,
+	NoJokersLeft,
+	AlreadyUsed,
+	NoHint,
+	Eliminated,
+	Paused,
+}
+
+impl fmt::Display for JokerError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			JokerError::WrongState => write!(f, "Game lobby is in wrong state!"),
+			JokerError::PlayerNotFound => write!(f, "Player(you) not found"),
+			JokerError::NoJokersLeft => write!(f, "You have no jokers of this type!"),
+			JokerError::AlreadyUsed => write!(f, "You already used this joker for the question!"),
+			JokerError::NoHint => write!(f, "This question has no hint!"),
+			JokerError::Eliminated => write!(f, "You are eliminated!"),
+			JokerError::Paused => write!(f, "Game is paused!"),
+		}
+	}
+}
+
+impl std::error::Error for JokerError {}
+
+//running audience poll for the current question; the histogram is sent
+// privately to the requesting players on every vote
+#[derive(Clone, Default)]
+pub struct AudiencePoll {
+	pub requesters: Vec<String>,       //UUIDs of players that used the joker
+	pub votes: HashMap<String, usize>, //answer of each spectator by UUID
+}
+
+impl AudiencePoll {
+	//count votes per answer option, optionally including given player answers
+	pub fn histogram(&self, player_answers: &[usize]) -> Vec<usize> {
+		let mut histogram = vec![0; 4];
+		for answer in self.votes.values().chain(player_answers) {
+			if (1..=4).contains(answer) {
+				histogram[answer - 1] += 1;
+			}
+		}
+		histogram
+	}
+}
+
+//random range of numbers that contains the correct answer
+pub fn estimation_range(correct_answer: usize) -> (usize, usize) {
+	let width = (correct_answer / 4).max(2);
+	let offset = rand::thread_rng().gen_range(0..=width);
+	let min = correct_answer.saturating_sub(offset);
+	(min, min.saturating_add(width))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn estimation_range_contains_answer() {
+		for correct_answer in [0, 1, 2, 7, 100, 12345, usize::MAX / 2, usize::MAX] {
+			for _ in 0..100 {
+				let (min, max) = estimation_range(correct_answer);
+				assert!(min <= correct_answer && correct_answer <= max);
+			}
+		}
+	}
+
+	#[test]
+	fn estimation_range_width() {
+		for _ in 0..100 {
+			let (min, max) = estimation_range(1000);
+			assert_eq!(max - min, 250);
+			let (min, max) = estimation_range(3);
+			assert_eq!(max - min, 2);
+		}
+	}
+
+	#[test]
+	fn histogram_counts_votes() {
+		let mut poll = AudiencePoll::default();
+		assert_eq!(poll.histogram(&[]), vec![0, 0, 0, 0]);
+		poll.votes.insert("a".to_owned(), 1);
+		poll.votes.insert("b".to_owned(), 3);
+		poll.votes.insert("c".to_owned(), 3);
+		assert_eq!(poll.histogram(&[]), vec![1, 0, 2, 0]);
+		//player answers are counted too, unanswered and invalid ones are not
+		assert_eq!(poll.histogram(&[4, 1, 0, 5]), vec![2, 0, 2, 1]);
+	}
+}
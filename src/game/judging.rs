@@ -0,0 +1,47 @@
+use std::{collections::HashMap, fmt};
+
+use super::{state::LobbyState, PlayerData};
+
+//host's verdicts for a question that is judged manually
+pub struct Judging {
+	pub scoring_state: LobbyState, //state that scores the question once judging is done
+	pub verdicts: HashMap<String, bool>, //whether the answer is correct, by player UUID
+}
+
+//reasons a verdict can not be recorded
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum JudgeError {
+	WrongState,
+	PlayerNotFound,
+}
+
+impl fmt::Display for JudgeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			JudgeError::WrongState => write!(f, "Game lobby is in wrong state!"),
+			JudgeError::PlayerNotFound => write!(f, "Player not found"),
+		}
+	}
+}
+
+impl std::error::Error for JudgeError {}
+
+impl Judging {
+	pub fn new(scoring_state: LobbyState) -> Self {
+		Judging { scoring_state, verdicts: HashMap::new() }
+	}
+}
+
+//whether the player answered correctly; the host's verdict overrides comparing
+// the answer if the question was judged
+pub fn is_correct(judging: Option<&Judging>, player: &PlayerData, correct_answer: usize) -> bool {
+	judging
+		.and_then(|judging| judging.verdicts.get(&player.uuid))
+		.copied()
+		.unwrap_or(player.answer == correct_answer)
+}
+
+//host's verdict for the player, if the question was judged
+pub fn verdict(judging: Option<&Judging>, player: &PlayerData) -> Option<bool> {
+	judging.and_then(|judging| judging.verdicts.get(&player.uuid)).copied()
+}
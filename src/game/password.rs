@@ -0,0 +1,35 @@
+use pbkdf2::pbkdf2_hmac;
+use rand::RngCore;
+use sha2::Sha256;
+
+const SALT_LENGTH: usize = 16; //random bytes hashed together with the password
+const ROUNDS: u32 = 100_000; //PBKDF2 iterations, to slow down guessing
+
+//salted hash of a lobby password, the password itself is never stored
+pub struct PasswordHash {
+	salt: [u8; SALT_LENGTH],
+	hash: [u8; 32],
+}
+
+impl PasswordHash {
+	pub fn new(password: &str) -> Self {
+		let mut salt = [0; SALT_LENGTH];
+		rand::thread_rng().fill_bytes(&mut salt);
+		PasswordHash { salt, hash: hash_password(&salt, password) }
+	}
+
+	pub fn verify(&self, password: &str) -> bool {
+		//compare all bytes so the time taken does not depend on the password
+		hash_password(&self.salt, password)
+			.iter()
+			.zip(self.hash.iter())
+			.fold(0, |diff, (a, b)| diff | (a ^ b))
+			== 0
+	}
+}
+
+fn hash_password(salt: &[u8], password: &str) -> [u8; 32] {
+	let mut hash = [0; 32];
+	pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, ROUNDS, &mut hash);
+	hash
+}
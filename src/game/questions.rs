@@ -1,36 +1,389 @@
 use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
 	fs,
+	io::{self, Write},
 	path::{Path, PathBuf},
 };
 
 use serde::{Deserialize, Serialize};
 
 const QUESTIONS_FOLDER: &str = "./questions";
+const MAX_QUESTION_SET_NAME_LENGTH: usize = 50;
+const MAX_LANGUAGE_CODE_LENGTH: usize = 10;
 
 //different gameshow question types
-#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum QuestionType {
 	NormalQuestion,
 	BettingQuestion,
 	EstimationQuestion,
 	VersusQuestion,
+	FinalQuestion,
+}
+
+//text that is either the same in every language or translated, keyed by
+// language code
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum LocalizedText {
+	Plain(String),
+	Translated(BTreeMap<String, String>),
+}
+
+impl LocalizedText {
+	//get the text in the requested language, falling back to the fallback
+	// language and then to any available translation
+	pub fn resolve(&self, language: &str, fallback: &str) -> String {
+		match self {
+			LocalizedText::Plain(text) => text.clone(),
+			LocalizedText::Translated(texts) => texts
+				.get(language)
+				.or_else(|| texts.get(fallback))
+				.or_else(|| texts.values().next())
+				.cloned()
+				.unwrap_or_default(),
+		}
+	}
+
+	fn add_languages(&self, languages: &mut BTreeSet<String>) {
+		if let LocalizedText::Translated(texts) = self {
+			languages.extend(texts.keys().cloned());
+		}
+	}
+}
+
+impl From<String> for LocalizedText {
+	fn from(text: String) -> Self {
+		LocalizedText::Plain(text)
+	}
 }
 
 //struct for question data
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Question {
 	pub question_type: QuestionType,
-	pub category: String,
-	pub question: String,
-	pub answers: Vec<String>,
+	pub category: LocalizedText,
+	pub question: LocalizedText,
+	pub answers: Vec<LocalizedText>,
 	pub correct_answer: usize,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub hint: Option<LocalizedText>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub value: Option<i64>, //point value on the board, replaces the ruleset's payout
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub host_judges: bool, //the admin judges the answers instead of comparing them
+}
+
+impl Question {
+	pub fn category(&self, language: &str, fallback: &str) -> String {
+		self.category.resolve(language, fallback)
+	}
+
+	pub fn question(&self, language: &str, fallback: &str) -> String {
+		self.question.resolve(language, fallback)
+	}
+
+	pub fn answers(&self, language: &str, fallback: &str) -> Vec<String> {
+		self.answers.iter().map(|answer| answer.resolve(language, fallback)).collect()
+	}
+
+	//all languages any of the question's texts are translated to
+	pub fn languages(&self) -> BTreeSet<String> {
+		let mut languages = BTreeSet::new();
+		self.category.add_languages(&mut languages);
+		self.question.add_languages(&mut languages);
+		for answer in self.answers.iter() {
+			answer.add_languages(&mut languages);
+		}
+		languages
+	}
+
+	//the question's texts for every language it is translated to
+	pub fn translations(&self, fallback: &str) -> BTreeMap<String, QuestionTranslation> {
+		self.languages()
+			.into_iter()
+			.map(|language| {
+				let translation = QuestionTranslation {
+					category: Some(self.category(&language, fallback)),
+					question: Some(self.question(&language, fallback)),
+					answers: Some(self.answers(&language, fallback)),
+				};
+				(language, translation)
+			})
+			.collect()
+	}
+}
+
+//translated texts of a question sent to clients; only contains the texts
+// that are already revealed
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QuestionTranslation {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub category: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub question: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub answers: Option<Vec<String>>,
+}
+
+impl QuestionTranslation {
+	pub fn category_only(self) -> Self {
+		QuestionTranslation { category: self.category, question: None, answers: None }
+	}
+
+	pub fn without_category(self) -> Self {
+		QuestionTranslation { category: None, ..self }
+	}
+
+	pub fn without_answers(self) -> Self {
+		QuestionTranslation { answers: None, ..self }
+	}
+}
+
+//how hard a question set is
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum Difficulty {
+	Easy,
+	Medium,
+	Hard,
+}
+
+//optional descriptive data of a question set
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct QuestionSetMeta {
+	pub title: Option<String>,
+	pub description: Option<String>,
+	pub language: Option<String>,
+	pub author: Option<String>,
+	pub tags: Vec<String>,
+	pub difficulty: Option<Difficulty>,
+	pub estimated_duration: Option<u32>, //in minutes
+}
+
+//content of a question file; either a bare list of questions or questions
+// wrapped together with metadata
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum QuestionSetFile {
+	Questions(Vec<Question>),
+	WithMeta {
+		#[serde(default)]
+		meta: QuestionSetMeta,
+		questions: Vec<Question>,
+	},
+	WithRounds {
+		#[serde(default)]
+		meta: QuestionSetMeta,
+		rounds: Vec<Round>,
+	},
+}
+
+impl QuestionSetFile {
+	pub fn into_parts(self) -> (QuestionSetMeta, Vec<Question>) {
+		match self {
+			QuestionSetFile::Questions(questions) => (QuestionSetMeta::default(), questions),
+			QuestionSetFile::WithMeta { meta, questions } => (meta, questions),
+			QuestionSetFile::WithRounds { meta, rounds } => {
+				(meta, rounds.into_iter().flat_map(|round| round.questions).collect())
+			}
+		}
+	}
+
+	pub fn into_questions(self) -> Vec<Question> {
+		self.into_parts().1
+	}
+
+	//round multipliers must be positive
+	pub fn is_valid(&self) -> bool {
+		match self {
+			QuestionSetFile::WithRounds { rounds, .. } => {
+				rounds.iter().all(|round| round.multiplier >= 1)
+			}
+			_ => true,
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		match self {
+			QuestionSetFile::Questions(questions) | QuestionSetFile::WithMeta { questions, .. } => {
+				questions.is_empty()
+			}
+			QuestionSetFile::WithRounds { rounds, .. } => {
+				rounds.iter().all(|round| round.questions.is_empty())
+			}
+		}
+	}
+
+	//flat list of questions and the rounds they are grouped in (empty if
+	// the set has no rounds)
+	pub fn into_questions_and_rounds(self) -> (Vec<Question>, Vec<RoundInfo>) {
+		match self {
+			QuestionSetFile::WithRounds { rounds, .. } => {
+				let mut questions = Vec::new();
+				let mut round_infos = Vec::new();
+				for round in rounds.into_iter().filter(|round| !round.questions.is_empty()) {
+					round_infos.push(RoundInfo {
+						name: round.name,
+						intro: round.intro,
+						multiplier: round.multiplier,
+						first_question: questions.len() + 1,
+						num_questions: round.questions.len(),
+					});
+					questions.extend(round.questions);
+				}
+				(questions, round_infos)
+			}
+			question_set => (question_set.into_questions(), Vec::new()),
+		}
+	}
+}
+
+//named group of questions in a question file, played with an intro before
+// and a summary after it
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Round {
+	pub name: LocalizedText,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub intro: Option<LocalizedText>,
+	#[serde(default = "default_multiplier")]
+	pub multiplier: i64, //money won or lost in the round's questions is multiplied by this
+	pub questions: Vec<Question>,
+}
+
+fn default_multiplier() -> i64 {
+	1
+}
+
+//round of the loaded questions, without the questions themselves
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoundInfo {
+	pub name: LocalizedText,
+	pub intro: Option<LocalizedText>,
+	pub multiplier: i64,
+	pub first_question: usize, //id of the round's first question (starting at 1)
+	pub num_questions: usize,
+}
+
+impl RoundInfo {
+	pub fn last_question(&self) -> usize {
+		self.first_question + self.num_questions - 1
+	}
+
+	pub fn contains(&self, question_id: usize) -> bool {
+		question_id >= self.first_question && question_id <= self.last_question()
+	}
+
+	//the round's texts for every language they are translated to
+	pub fn translations(&self, fallback: &str) -> BTreeMap<String, RoundTranslation> {
+		let mut languages = BTreeSet::new();
+		self.name.add_languages(&mut languages);
+		if let Some(intro) = &self.intro {
+			intro.add_languages(&mut languages);
+		}
+		languages
+			.into_iter()
+			.map(|language| {
+				let translation = RoundTranslation {
+					name: self.name.resolve(&language, fallback),
+					intro: self.intro.as_ref().map(|intro| intro.resolve(&language, fallback)),
+				};
+				(language, translation)
+			})
+			.collect()
+	}
+}
+
+//translated texts of a round sent to clients
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoundTranslation {
+	pub name: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub intro: Option<String>,
+}
+
+//information about a server side question set for choosing one
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QuestionSetInfo {
+	pub name: String,
+	#[serde(skip)]
+	pub path: PathBuf,
+	pub revision: Option<usize>, //latest revision of library question sets
+	pub meta: QuestionSetMeta,
+	pub num_questions: usize,
+	pub question_counts: HashMap<QuestionType, usize>,
+}
+
+impl QuestionSetInfo {
+	pub fn new(name: String, path: PathBuf, meta: QuestionSetMeta, questions: &[Question]) -> Self {
+		let mut question_counts = HashMap::new();
+		for question in questions.iter() {
+			*question_counts.entry(question.question_type).or_insert(0) += 1;
+		}
+		QuestionSetInfo {
+			name,
+			path,
+			revision: None,
+			meta,
+			num_questions: questions.len(),
+			question_counts,
+		}
+	}
+
+	pub fn with_revision(mut self, revision: usize) -> Self {
+		self.revision = Some(revision);
+		self
+	}
 }
 
-//read questions from a JSON file and return them
-pub fn read_questions(filename: impl AsRef<Path>) -> std::io::Result<Vec<Question>> {
+//file formats questions can be exported to
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+	Json,
+	Csv,
+	Gift,
+}
+
+impl ExportFormat {
+	pub fn extension(&self) -> &'static str {
+		match self {
+			ExportFormat::Json => "json",
+			ExportFormat::Csv => "csv",
+			ExportFormat::Gift => "gift",
+		}
+	}
+
+	pub fn content_type(&self) -> &'static str {
+		match self {
+			ExportFormat::Json => "application/json",
+			ExportFormat::Csv => "text/csv; charset=utf-8",
+			ExportFormat::Gift => "text/plain; charset=utf-8",
+		}
+	}
+}
+
+//read a question set with its metadata and rounds (if available) from a JSON
+// file
+pub fn read_question_set(filename: impl AsRef<Path>) -> std::io::Result<QuestionSetFile> {
 	let json_string = fs::read_to_string(filename)?;
-	let questions: Vec<Question> = serde_json::from_str(&json_string)?;
-	Ok(questions)
+	let question_set: QuestionSetFile = serde_json::from_str(&json_string)?;
+	if !question_set.is_valid() {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid round multiplier!"));
+	}
+	Ok(question_set)
+}
+
+//load a question set file by name; the custom set starts out empty
+pub fn load_question_set(question_set: &str) -> std::io::Result<QuestionSetFile> {
+	if question_set != "custom" {
+		for (name, file) in find_question_files()? {
+			if name == question_set {
+				return read_question_set(file);
+			}
+		}
+	}
+	Ok(QuestionSetFile::Questions(Vec::new()))
 }
 
 pub fn find_question_files() -> std::io::Result<Vec<(String, PathBuf)>> {
@@ -56,3 +409,356 @@ pub fn find_question_files() -> std::io::Result<Vec<(String, PathBuf)>> {
 	list.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 	Ok(list)
 }
+
+//find all question files and load their metadata; invalid files are skipped
+pub fn find_question_sets() -> std::io::Result<Vec<QuestionSetInfo>> {
+	let mut list = Vec::new();
+	for (name, path) in find_question_files()? {
+		match read_question_set(&path) {
+			Ok(question_set) => {
+				let (meta, questions) = question_set.into_parts();
+				list.push(QuestionSetInfo::new(name, path, meta, &questions));
+			}
+			Err(err) => eprintln!("Skipping invalid question set {}: {}", path.display(), err),
+		}
+	}
+	Ok(list)
+}
+
+//check if a string looks like a language code, e.g. "en" or "pt-BR"
+pub fn is_valid_language_code(language: &str) -> bool {
+	!language.is_empty()
+		&& language.len() <= MAX_LANGUAGE_CODE_LENGTH
+		&& language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+//check if a name can be used as file name for a saved question set
+pub fn is_valid_question_set_name(name: &str) -> bool {
+	!name.is_empty()
+		&& name.len() <= MAX_QUESTION_SET_NAME_LENGTH
+		&& name != "custom"
+		&& name != "questions-example"
+		&& !name.starts_with(['-', '_'])
+		&& name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+//save a question set as a new question set file, keeping its metadata and
+// rounds; fails if the set already exists
+pub fn save_question_set(name: &str, question_set: &QuestionSetFile) -> std::io::Result<PathBuf> {
+	if !is_valid_question_set_name(name) {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid question set name!"));
+	}
+
+	let path = Path::new(QUESTIONS_FOLDER).join(format!("{}.json", name));
+	let json_string = serde_json::to_string_pretty(question_set)?;
+	let mut file = fs::OpenOptions::new().write(true).create_new(true).open(&path)?;
+	file.write_all(json_string.as_bytes())?;
+	Ok(path)
+}
+
+//convert a question set to the content of a file in the given format; texts
+// are exported in the given language, except for JSON which keeps all
+// translations, the metadata and the rounds
+pub fn export_questions(
+	question_set: &QuestionSetFile,
+	format: ExportFormat,
+	language: &str,
+) -> std::io::Result<String> {
+	match format {
+		ExportFormat::Json => Ok(serde_json::to_string_pretty(question_set)?),
+		ExportFormat::Csv => Ok(export_csv(&question_set.clone().into_questions(), language)),
+		ExportFormat::Gift => Ok(export_gift(&question_set.clone().into_questions(), language)),
+	}
+}
+
+fn question_type_name(question_type: QuestionType) -> &'static str {
+	match question_type {
+		QuestionType::NormalQuestion => "NormalQuestion",
+		QuestionType::BettingQuestion => "BettingQuestion",
+		QuestionType::EstimationQuestion => "EstimationQuestion",
+		QuestionType::VersusQuestion => "VersusQuestion",
+		QuestionType::FinalQuestion => "FinalQuestion",
+	}
+}
+
+//one line per question, answers get as many columns as the longest answer list
+fn export_csv(questions: &[Question], language: &str) -> String {
+	let num_answers = questions.iter().map(|q| q.answers.len()).max().unwrap_or(0);
+
+	let mut header = vec![
+		"question_type".to_owned(),
+		"category".to_owned(),
+		"question".to_owned(),
+		"correct_answer".to_owned(),
+	];
+	header.extend((1..=num_answers).map(|i| format!("answer_{}", i)));
+
+	let mut csv = header.join(",") + "\r\n";
+	for question in questions.iter() {
+		let answers = question.answers(language, language);
+		let mut fields = vec![
+			question_type_name(question.question_type).to_owned(),
+			csv_escape(&question.category(language, language)),
+			csv_escape(&question.question(language, language)),
+			question.correct_answer.to_string(),
+		];
+		fields.extend(
+			(0..num_answers).map(|i| answers.get(i).map(|a| csv_escape(a)).unwrap_or_default()),
+		);
+		csv += &fields.join(",");
+		csv += "\r\n";
+	}
+	csv
+}
+
+fn csv_escape(field: &str) -> String {
+	if field.contains([',', '"', '\r', '\n']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_owned()
+	}
+}
+
+//Moodle GIFT format; estimation questions become numerical questions, the
+// special question types are noted in a comment
+fn export_gift(questions: &[Question], language: &str) -> String {
+	let mut gift = String::new();
+	let mut current_category = None;
+	for (i, question) in questions.iter().enumerate() {
+		let category = question.category(language, language);
+		if current_category.as_ref() != Some(&category) {
+			gift += &format!("$CATEGORY: {}\n\n", gift_escape(&category));
+			current_category = Some(category);
+		}
+		if question.question_type != QuestionType::NormalQuestion {
+			gift += &format!("// {}\n", question_type_name(question.question_type));
+		}
+		gift +=
+			&format!("::Q{}:: {} {{", i + 1, gift_escape(&question.question(language, language)));
+		if question.question_type == QuestionType::EstimationQuestion {
+			gift += &format!("#{}", question.correct_answer);
+		} else {
+			for (j, answer) in question.answers(language, language).iter().enumerate() {
+				let marker = if j + 1 == question.correct_answer { '=' } else { '~' };
+				gift += &format!("\n\t{}{}", marker, gift_escape(answer));
+			}
+			gift += "\n";
+		}
+		gift += "}\n\n";
+	}
+	gift
+}
+
+fn gift_escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'~' | '=' | '#' | '{' | '}' | ':' | '\\' => {
+				escaped.push('\\');
+				escaped.push(c);
+			}
+			'\n' => escaped.push_str("\\n"),
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn question(question_type: QuestionType, question: &str, correct_answer: usize) -> Question {
+		Question {
+			question_type,
+			category: LocalizedText::from(String::from("Geo")),
+			question: LocalizedText::from(String::from(question)),
+			answers: ["Paris", "Rome, Italy", "Berlin", "Madrid"]
+				.into_iter()
+				.map(|answer| LocalizedText::from(String::from(answer)))
+				.collect(),
+			correct_answer,
+			hint: None,
+			value: None,
+			host_judges: false,
+		}
+	}
+
+	#[test]
+	fn valid_question_set_names() {
+		assert!(is_valid_question_set_name("quiz-night_2"));
+		assert!(!is_valid_question_set_name(""));
+		assert!(!is_valid_question_set_name("custom"));
+		assert!(!is_valid_question_set_name("questions-example"));
+		assert!(!is_valid_question_set_name("-quiz"));
+		assert!(!is_valid_question_set_name("_quiz"));
+		assert!(!is_valid_question_set_name("../quiz"));
+		assert!(!is_valid_question_set_name("quiz night"));
+		assert!(!is_valid_question_set_name(&"q".repeat(MAX_QUESTION_SET_NAME_LENGTH + 1)));
+		assert!(is_valid_question_set_name(&"q".repeat(MAX_QUESTION_SET_NAME_LENGTH)));
+	}
+
+	#[test]
+	fn csv_fields_are_escaped() {
+		assert_eq!(csv_escape("plain"), "plain");
+		assert_eq!(csv_escape("a,b"), "\"a,b\"");
+		assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+		assert_eq!(csv_escape("two\nlines"), "\"two\nlines\"");
+	}
+
+	#[test]
+	fn gift_text_is_escaped() {
+		assert_eq!(gift_escape("plain"), "plain");
+		assert_eq!(gift_escape("1+1=2 ~ {x}: #1 \\"), "1+1\\=2 \\~ \\{x\\}\\: \\#1 \\\\");
+		assert_eq!(gift_escape("two\nlines"), "two\\nlines");
+	}
+
+	#[test]
+	fn export_csv_output() {
+		let question_set = QuestionSetFile::Questions(vec![
+			question(QuestionType::NormalQuestion, "Capital of Italy?", 2),
+			question(QuestionType::EstimationQuestion, "Year?", 1990),
+		]);
+		let csv = export_questions(&question_set, ExportFormat::Csv, "en").unwrap();
+		assert_eq!(
+			csv,
+			"question_type,category,question,correct_answer,answer_1,answer_2,answer_3,answer_4\r\n\
+			 NormalQuestion,Geo,Capital of Italy?,2,Paris,\"Rome, Italy\",Berlin,Madrid\r\n\
+			 EstimationQuestion,Geo,Year?,1990,Paris,\"Rome, Italy\",Berlin,Madrid\r\n"
+		);
+	}
+
+	#[test]
+	fn export_gift_output() {
+		let question_set = QuestionSetFile::Questions(vec![
+			question(QuestionType::NormalQuestion, "Capital of Italy?", 2),
+			question(QuestionType::EstimationQuestion, "Year?", 1990),
+		]);
+		let gift = export_questions(&question_set, ExportFormat::Gift, "en").unwrap();
+		assert_eq!(
+			gift,
+			"$CATEGORY: Geo\n\n\
+			 ::Q1:: Capital of Italy? {\n\t~Paris\n\t=Rome, Italy\n\t~Berlin\n\t~Madrid\n}\n\n\
+			 // EstimationQuestion\n\
+			 ::Q2:: Year? {#1990}\n\n"
+		);
+	}
+
+	#[test]
+	fn export_json_keeps_the_question_set() {
+		let question_set = QuestionSetFile::WithMeta {
+			meta: QuestionSetMeta { title: Some(String::from("Geo")), ..Default::default() },
+			questions: vec![question(QuestionType::NormalQuestion, "Capital of Italy?", 2)],
+		};
+		let json = export_questions(&question_set, ExportFormat::Json, "en").unwrap();
+		let exported: QuestionSetFile = serde_json::from_str(&json).unwrap();
+		match exported {
+			QuestionSetFile::WithMeta { meta, questions } => {
+				assert_eq!(meta.title.as_deref(), Some("Geo"));
+				assert_eq!(questions.len(), 1);
+			}
+			_ => panic!("metadata was lost"),
+		}
+	}
+
+	const QUESTION_JSON: &str = r#"{"question_type":"NormalQuestion","category":"Geo",
+		"question":"Capital of Italy?","answers":["Paris","Rome","Berlin","Madrid"],
+		"correct_answer":2}"#;
+
+	#[test]
+	fn question_files_without_metadata() {
+		let json = format!("[{}]", QUESTION_JSON);
+		let question_set: QuestionSetFile = serde_json::from_str(&json).unwrap();
+		assert!(
+			matches!(&question_set, QuestionSetFile::Questions(questions) if questions.len() == 1)
+		);
+		let (meta, questions) = question_set.into_parts();
+		assert!(meta.title.is_none());
+		assert_eq!(questions.len(), 1);
+	}
+
+	#[test]
+	fn question_files_with_metadata() {
+		let json = format!(
+			r#"{{"meta":{{"title":"Geo","difficulty":"Hard","tags":["europe"]}},"questions":[{}]}}"#,
+			QUESTION_JSON
+		);
+		let question_set: QuestionSetFile = serde_json::from_str(&json).unwrap();
+		assert!(matches!(question_set, QuestionSetFile::WithMeta { .. }));
+		let (meta, questions) = question_set.into_parts();
+		assert_eq!(meta.title.as_deref(), Some("Geo"));
+		assert!(meta.difficulty == Some(Difficulty::Hard));
+		assert_eq!(meta.tags, vec![String::from("europe")]);
+		assert_eq!(questions.len(), 1);
+
+		//the metadata is optional
+		let json = format!(r#"{{"questions":[{}]}}"#, QUESTION_JSON);
+		let question_set: QuestionSetFile = serde_json::from_str(&json).unwrap();
+		assert!(matches!(question_set, QuestionSetFile::WithMeta { .. }));
+	}
+
+	#[test]
+	fn localized_texts() {
+		let plain: LocalizedText = serde_json::from_str(r#""Rome""#).unwrap();
+		assert!(matches!(plain, LocalizedText::Plain(_)));
+		assert_eq!(plain.resolve("de", "en"), "Rome");
+
+		let translated: LocalizedText =
+			serde_json::from_str(r#"{"en":"Rome","it":"Roma"}"#).unwrap();
+		assert!(matches!(translated, LocalizedText::Translated(_)));
+		assert_eq!(translated.resolve("it", "en"), "Roma");
+		assert_eq!(translated.resolve("de", "en"), "Rome");
+		assert_eq!(translated.resolve("de", "fr"), "Rome");
+	}
+
+	#[test]
+	fn translated_questions() {
+		let json = r#"{"question_type":"NormalQuestion","category":{"en":"Geo","de":"Erdkunde"},
+			"question":"Capital of Italy?","answers":["Paris",{"en":"Rome","it":"Roma"},"Berlin",
+			"Madrid"],"correct_answer":2}"#;
+		let question: Question = serde_json::from_str(json).unwrap();
+		assert_eq!(
+			question.languages().into_iter().collect::<Vec<_>>(),
+			vec![String::from("de"), String::from("en"), String::from("it")]
+		);
+		assert_eq!(question.category("de", "en"), "Erdkunde");
+		assert_eq!(question.category("it", "en"), "Geo");
+		assert_eq!(question.answers("it", "en")[1], "Roma");
+		assert_eq!(question.question("it", "en"), "Capital of Italy?");
+	}
+
+	#[test]
+	fn question_files_with_rounds() {
+		let json = format!(
+			r#"{{"meta":{{"title":"Geo"}},"rounds":[{{"name":"Warmup","questions":[{q},{q}]}},
+			{{"name":"Empty","questions":[]}},
+			{{"name":"Finale","intro":"Double money","multiplier":2,"questions":[{q}]}}]}}"#,
+			q = QUESTION_JSON
+		);
+		let question_set: QuestionSetFile = serde_json::from_str(&json).unwrap();
+		assert!(matches!(question_set, QuestionSetFile::WithRounds { .. }));
+		assert!(question_set.is_valid());
+		assert!(!question_set.is_empty());
+
+		//empty rounds are dropped, the rest is numbered across rounds
+		let (questions, rounds) = question_set.into_questions_and_rounds();
+		assert_eq!(questions.len(), 3);
+		assert_eq!(rounds.len(), 2);
+		assert_eq!((rounds[0].multiplier, rounds[0].first_question), (1, 1));
+		assert_eq!(rounds[0].last_question(), 2);
+		assert_eq!((rounds[1].multiplier, rounds[1].first_question), (2, 3));
+		assert!(rounds[1].contains(3) && !rounds[1].contains(2) && !rounds[1].contains(4));
+	}
+
+	#[test]
+	fn round_multipliers_must_be_positive() {
+		for multiplier in [0, -1] {
+			let json = format!(
+				r#"{{"rounds":[{{"name":"Round","multiplier":{},"questions":[{}]}}]}}"#,
+				multiplier, QUESTION_JSON
+			);
+			let question_set: QuestionSetFile = serde_json::from_str(&json).unwrap();
+			assert!(!question_set.is_valid());
+		}
+	}
+}
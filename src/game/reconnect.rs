@@ -0,0 +1,28 @@
+use std::fmt;
+
+pub const TOKEN_LENGTH: usize = 32; //random characters of a reconnect token
+
+//reasons a reconnect token can not be redeemed
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReconnectError {
+	InvalidToken,
+	AlreadyJoined,
+}
+
+impl fmt::Display for ReconnectError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ReconnectError::InvalidToken => write!(f, "Reconnect token is invalid!"),
+			ReconnectError::AlreadyJoined => write!(f, "You already joined this lobby!"),
+		}
+	}
+}
+
+impl std::error::Error for ReconnectError {}
+
+//move a reference to the player to the new UUID
+pub fn rebind(uuid: &mut String, old: &str, new: &str) {
+	if uuid == old {
+		*uuid = new.to_owned();
+	}
+}
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+
+//standard scoring rules, see Ruleset
+const NORMAL_Q_MONEY: i64 = 500; //money to get when answering a normal question correctly
+const ESTIMATION_Q_MONEY: i64 = 1000; //money to get when winning a estimation question
+const VERSUS_CORRECT_FACTOR: f64 = 0.5; //factor for the attacked player if the attacker is right
+const VERSUS_WRONG_FACTOR: f64 = 2.0; //factor for the attacked player if the attacker is wrong
+const MIN_MONEY: i64 = 1; //money of players is never less, to allow continuing the game
+
+//scoring rules of a lobby for all question types
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Ruleset {
+	pub normal_q_money: i64,        //payout for a correct normal question
+	pub normal_q_penalty: i64,      //money lost for a wrong normal question
+	pub estimation_q_money: i64,    //payout for the closest estimation(s)
+	pub betting_q_factor: i64,      //a correct bet pays out the bet times this factor
+	pub versus_correct_factor: f64, //factor for the attacked player if the attacker is right
+	pub versus_wrong_factor: f64,   //factor for the attacked player if the attacker is wrong
+	pub min_money: i64,             //floor for the money of players after each question
+	pub min_bet: i64,               //smallest allowed bet, unless betting all money
+	pub max_bet: Option<i64>,       //largest allowed bet, players' money if none
+	pub elimination: bool,          //bankrupt players are out instead of the money floor
+	pub elimination_checkpoints: Vec<usize>, //questions after which the last players are out
+	pub eliminations_per_checkpoint: usize, //players eliminated at each checkpoint
+}
+
+impl Default for Ruleset {
+	fn default() -> Self {
+		Ruleset {
+			normal_q_money: NORMAL_Q_MONEY,
+			normal_q_penalty: 0,
+			estimation_q_money: ESTIMATION_Q_MONEY,
+			betting_q_factor: 1,
+			versus_correct_factor: VERSUS_CORRECT_FACTOR,
+			versus_wrong_factor: VERSUS_WRONG_FACTOR,
+			min_money: MIN_MONEY,
+			min_bet: 1,
+			max_bet: None,
+			elimination: false,
+			elimination_checkpoints: Vec::new(),
+			eliminations_per_checkpoint: 1,
+		}
+	}
+}
+
+impl Ruleset {
+	//whether the rules can be used for a game
+	pub fn is_valid(&self) -> bool {
+		self.normal_q_money >= 0
+			&& self.normal_q_penalty >= 0
+			&& self.estimation_q_money >= 0
+			&& self.betting_q_factor >= 1
+			&& self.versus_correct_factor.is_finite()
+			&& self.versus_correct_factor >= 0.0
+			&& self.versus_wrong_factor.is_finite()
+			&& self.versus_wrong_factor >= 0.0
+			&& self.min_money >= 0
+			&& self.min_bet >= 1
+			&& self.max_bet.is_none_or(|max_bet| max_bet >= self.min_bet)
+	}
+
+	//money change for a normal question (without jokers)
+	pub fn normal_q_result(&self, correct: bool) -> i64 {
+		if correct {
+			self.normal_q_money
+		} else {
+			-self.normal_q_penalty
+		}
+	}
+
+	//money change for an estimation question (without jokers)
+	pub fn estimation_q_result(&self, closest: bool) -> i64 {
+		if closest {
+			self.estimation_q_money
+		} else {
+			0
+		}
+	}
+
+	//money change for a betting question (without jokers)
+	pub fn betting_q_result(&self, money_bet: i64, correct: bool) -> i64 {
+		if correct {
+			money_bet.saturating_mul(self.betting_q_factor)
+		} else {
+			money_bet.saturating_neg()
+		}
+	}
+
+	//factor for the money of an attacked player
+	pub fn versus_factor(&self, attacker_correct: bool) -> f64 {
+		if attacker_correct {
+			self.versus_correct_factor
+		} else {
+			self.versus_wrong_factor
+		}
+	}
+
+	//use the question's own point value as payout if it has one
+	pub fn with_question_value(mut self, value: Option<i64>) -> Self {
+		if let Some(value) = value {
+			self.normal_q_money = value;
+			self.estimation_q_money = value;
+		}
+		self
+	}
+
+	//apply the money floor (players can go bankrupt in elimination mode)
+	pub fn limit_money(&self, money: i64) -> i64 {
+		if self.elimination {
+			money.max(0)
+		} else {
+			money.max(self.min_money)
+		}
+	}
+
+	//whether the lowest-ranked players are eliminated after the given question
+	pub fn is_elimination_checkpoint(&self, question_id: usize) -> bool {
+		self.elimination && self.elimination_checkpoints.contains(&question_id)
+	}
+
+	//whether a player with the given money may bet the given amount
+	pub fn is_valid_bet(&self, money_bet: i64, money: i64) -> bool {
+		money_bet >= 1
+			&& money_bet <= money
+			&& (money_bet >= self.min_bet || money_bet == money)
+			&& self.max_bet.is_none_or(|max_bet| money_bet <= max_bet)
+	}
+}
+
+//named rulesets to choose from
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum RulesetPreset {
+	Classic,     //the original rules
+	HighStakes,  //bigger payouts, but wrong answers cost money
+	Casual,      //small bets and mild versus questions
+	Elimination, //classic rules, but bankrupt and the last players are out
+}
+
+impl RulesetPreset {
+	pub fn ruleset(&self) -> Ruleset {
+		match self {
+			RulesetPreset::Classic => Ruleset::default(),
+			RulesetPreset::HighStakes => Ruleset {
+				normal_q_money: 1000,
+				normal_q_penalty: 500,
+				estimation_q_money: 2000,
+				betting_q_factor: 2,
+				versus_correct_factor: 0.25,
+				versus_wrong_factor: 4.0,
+				min_bet: 100,
+				..Ruleset::default()
+			},
+			RulesetPreset::Casual => Ruleset {
+				normal_q_money: 300,
+				estimation_q_money: 600,
+				versus_correct_factor: 0.75,
+				versus_wrong_factor: 1.5,
+				max_bet: Some(500),
+				..Ruleset::default()
+			},
+			RulesetPreset::Elimination => Ruleset {
+				elimination: true,
+				elimination_checkpoints: vec![5, 10, 15, 20, 25],
+				..Ruleset::default()
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PRESETS: [RulesetPreset; 4] = [
+		RulesetPreset::Classic,
+		RulesetPreset::HighStakes,
+		RulesetPreset::Casual,
+		RulesetPreset::Elimination,
+	];
+
+	#[test]
+	fn presets_are_valid() {
+		for preset in PRESETS {
+			assert!(preset.ruleset().is_valid());
+		}
+		assert_eq!(RulesetPreset::Classic.ruleset(), Ruleset::default());
+	}
+
+	#[test]
+	fn invalid_rulesets() {
+		assert!(!Ruleset { normal_q_money: -1, ..Ruleset::default() }.is_valid());
+		assert!(!Ruleset { betting_q_factor: 0, ..Ruleset::default() }.is_valid());
+		assert!(!Ruleset { versus_wrong_factor: f64::NAN, ..Ruleset::default() }.is_valid());
+		assert!(!Ruleset { versus_correct_factor: f64::INFINITY, ..Ruleset::default() }.is_valid());
+		assert!(!Ruleset { min_bet: 0, ..Ruleset::default() }.is_valid());
+		assert!(!Ruleset { min_bet: 100, max_bet: Some(50), ..Ruleset::default() }.is_valid());
+	}
+
+	#[test]
+	fn valid_bets() {
+		let ruleset = Ruleset { min_bet: 100, max_bet: Some(500), ..Ruleset::default() };
+		assert!(ruleset.is_valid_bet(100, 1000));
+		assert!(ruleset.is_valid_bet(500, 1000));
+		assert!(!ruleset.is_valid_bet(0, 1000));
+		assert!(!ruleset.is_valid_bet(-100, 1000));
+		assert!(!ruleset.is_valid_bet(99, 1000));
+		assert!(!ruleset.is_valid_bet(501, 1000));
+		assert!(!ruleset.is_valid_bet(300, 200));
+		//betting all money is allowed below the minimum bet
+		assert!(ruleset.is_valid_bet(50, 50));
+		assert!(!ruleset.is_valid_bet(50, 60));
+	}
+
+	#[test]
+	fn money_floor() {
+		let ruleset = Ruleset { min_money: 10, ..Ruleset::default() };
+		assert_eq!(ruleset.limit_money(-100), 10);
+		assert_eq!(ruleset.limit_money(5), 10);
+		assert_eq!(ruleset.limit_money(500), 500);
+
+		let ruleset = Ruleset { elimination: true, ..ruleset };
+		assert_eq!(ruleset.limit_money(-100), 0);
+		assert_eq!(ruleset.limit_money(5), 5);
+	}
+
+	#[test]
+	fn betting_results_saturate() {
+		let ruleset = Ruleset { betting_q_factor: 2, ..Ruleset::default() };
+		assert_eq!(ruleset.betting_q_result(100, true), 200);
+		assert_eq!(ruleset.betting_q_result(100, false), -100);
+		assert_eq!(ruleset.betting_q_result(i64::MAX, true), i64::MAX);
+	}
+}
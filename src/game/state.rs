@@ -1,9 +1,25 @@
-use std::{cmp::Ordering, sync::atomic};
+use std::{
+	cmp::{Ordering, Reverse},
+	collections::VecDeque,
+	sync::atomic,
+};
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
-use super::{events::*, make_public_player_data, questions::QuestionType};
+use super::{
+	board::{self, BoardChooser, BoardState, GameMode},
+	events::*,
+	jokers::JokerType,
+	judging::{self, Judging},
+	make_public_player_data,
+	questions::{QuestionType, RoundInfo},
+	reconnect,
+	ruleset::Ruleset,
+	PlayerData,
+};
+
+const MAX_SNAPSHOTS: usize = 10; //number of scoring steps that can be rolled back
 
 #[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
 pub enum LobbyState {
@@ -17,6 +33,83 @@ pub enum LobbyState {
 	VersusQSelecting(bool),
 	VersusQAnswering(bool),
 	GameEnding(bool),
+	RoundIntro(bool),
+	RoundSummary(bool),
+	FinalQWagering(bool),
+	FinalQAnswering(bool),
+	FinalQReveal(bool),
+	BoardSelecting(bool),
+	BuzzerQBuzzing(bool),
+	BuzzerQAnswering(bool),
+	Judging(bool),
+}
+
+//coarse state of a lobby as shown in the lobby list
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum LobbyPhase {
+	Menu,
+	Running,
+	Ending,
+}
+
+//progress of revealing the final question's results player by player
+#[derive(Default)]
+pub struct FinalReveal {
+	remaining: VecDeque<String>, //UUIDs of players not revealed yet
+	previous_player_data: Vec<PlayerData>,
+}
+
+impl FinalReveal {
+	//move the player to a new UUID
+	pub fn rebind(&mut self, old: &str, new: &str) {
+		for uuid in self.remaining.iter_mut() {
+			reconnect::rebind(uuid, old, new);
+		}
+		for player in self.previous_player_data.iter_mut() {
+			reconnect::rebind(&mut player.uuid, old, new);
+		}
+	}
+}
+
+//ways for the admin to leave the fixed question sequence
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
+pub enum Navigation {
+	Skip,        //continue with the next question without scoring the current one
+	Repeat,      //go back and play the previous question again
+	Jump(usize), //continue with the given question
+}
+
+//game before a question was scored, to roll back to
+pub struct GameSnapshot {
+	pub lobby_state: LobbyState, //state to continue in (answering the question again)
+	pub current_question: usize,
+	pub board: BoardState,
+	pub player_data: Vec<PlayerData>,
+}
+
+impl LobbyState {
+	pub fn phase(&self) -> LobbyPhase {
+		match self {
+			LobbyState::Menu(_) => LobbyPhase::Menu,
+			LobbyState::GameEnding(_) => LobbyPhase::Ending,
+			_ => LobbyPhase::Running,
+		}
+	}
+
+	//state to return to when rolling back the scoring started by this state
+	fn rollback_state(&self) -> Option<LobbyState> {
+		match self {
+			LobbyState::NormalQAnswering(true) => Some(LobbyState::NormalQAnswering(false)),
+			LobbyState::BettingQAnswering(true) => Some(LobbyState::BettingQAnswering(false)),
+			LobbyState::EstimationQAnswering(true) => Some(LobbyState::EstimationQAnswering(false)),
+			LobbyState::VersusQAnswering(true) => Some(LobbyState::VersusQAnswering(false)),
+			LobbyState::BuzzerQBuzzing(true) | LobbyState::BuzzerQAnswering(true) => {
+				Some(LobbyState::BuzzerQBuzzing(false))
+			}
+			LobbyState::FinalQAnswering(true) => Some(LobbyState::FinalQAnswering(false)),
+			_ => None,
+		}
+	}
 }
 
 //allow to go to the next state without checking any conditions
@@ -32,6 +125,15 @@ pub async fn initiate_next(lobby_state: &RwLock<LobbyState>) {
 		LobbyState::VersusQSelecting(false) => LobbyState::VersusQSelecting(true),
 		LobbyState::VersusQAnswering(false) => LobbyState::VersusQAnswering(true),
 		LobbyState::GameEnding(false) => LobbyState::GameEnding(true),
+		LobbyState::RoundIntro(false) => LobbyState::RoundIntro(true),
+		LobbyState::RoundSummary(false) => LobbyState::RoundSummary(true),
+		LobbyState::FinalQWagering(false) => LobbyState::FinalQWagering(true),
+		LobbyState::FinalQAnswering(false) => LobbyState::FinalQAnswering(true),
+		LobbyState::FinalQReveal(false) => LobbyState::FinalQReveal(true),
+		LobbyState::BoardSelecting(false) => LobbyState::BoardSelecting(true),
+		LobbyState::BuzzerQBuzzing(false) => LobbyState::BuzzerQBuzzing(true),
+		LobbyState::BuzzerQAnswering(false) => LobbyState::BuzzerQAnswering(true),
+		LobbyState::Judging(false) => LobbyState::Judging(true),
 		default => default,
 	};
 }
@@ -41,18 +143,46 @@ pub async fn initiate_next(lobby_state: &RwLock<LobbyState>) {
 pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finished (false=>repeat)
 {
 	let mut state = game.lobby_state.write().await;
+	//before a question is scored (and not judged yet), remember the game and
+	// let the host judge the answers if required
+	if let Some(rollback_state) = state.rollback_state() {
+		if game.judging.read().await.is_none() {
+			take_snapshot(game, rollback_state).await;
+			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			if (*game.questions.read().await)[question_id - 1].host_judges {
+				*game.judging.write().await = Some(Judging::new(*state));
+				let new_event =
+					EventType::BeginJudging(EventBeginJudging { current_question: question_id });
+				game.game_events.write().await.add(new_event);
+				*state = LobbyState::Judging(false);
+				return true;
+			}
+		}
+	}
 	match *state {
 		LobbyState::Menu(true) => {
 			//transition to first question (different states for different questions)
 			//prepare lobby, start question by setting state to LobbyState::Results(true)
 			// and transitioning again
 			game.current_question.store(0, atomic::Ordering::Relaxed);
+			let game_mode = *game.game_mode.read().await;
+			let special_jokers = game.special_jokers.read().await.clone();
+			let mut board_state = game.board.write().await;
+			*board_state = BoardState::default();
+			*game.judging.write().await = None;
+			game.snapshots.write().await.clear();
 			let mut player_access = game.player_data.write().await;
 			for player in (*player_access).iter_mut() {
 				//reset every player to starting conditions
 				player.money = game.param_initial_money.load(atomic::Ordering::Relaxed);
 				player.jokers = game.param_initial_jokers.load(atomic::Ordering::Relaxed);
+				player.special_jokers = special_jokers.clone();
+				player.eliminated = false;
+			}
+			if let GameMode::Board(chooser) = game_mode {
+				board_state.next_chooser(chooser, &player_access, &[]);
 			}
+			drop(board_state);
 			//create event (not for state transition yet)
 			let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
 				player_data: make_public_player_data(&player_access),
@@ -63,101 +193,96 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 			return false;
 		}
 		LobbyState::Results(true) => {
-			//transition to next question (different states for different questions)
-			//gather necessary data
-			let question_id = game.current_question.fetch_add(1, atomic::Ordering::Relaxed) + 1;
-			let questions = game.questions.read().await;
-			let num_questions = (*questions).len();
-			if question_id > num_questions {
-				//game ending - create event
-				let new_event = EventType::GameEnding(EventGameEnding {
-					player_data: make_public_player_data(&game.player_data.read().await),
-				});
-				game.game_events.write().await.add(new_event);
-				//set new question state
-				*state = LobbyState::GameEnding(false);
+			//in board mode a player picks the next question instead
+			if let GameMode::Board(chooser) = *game.game_mode.read().await {
+				begin_board_selection(game, &mut state, Some(chooser)).await;
+				return true;
+			}
+			//a finished round is summarized and a new one introduced before
+			// the next question
+			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let (ending_round, starting_round) = {
+				let rounds = game.rounds.read().await;
+				(
+					rounds.iter().position(|round| round.last_question() == question_id),
+					rounds.iter().position(|round| round.first_question == question_id + 1),
+				)
+			};
+			if let Some(round) = ending_round {
+				add_round_summary(game, round).await;
+				*state = LobbyState::RoundSummary(false);
+			} else if let Some(round) = starting_round {
+				add_round_intro(game, round).await;
+				*state = LobbyState::RoundIntro(false);
 			} else {
-				//next question
-				let question_type = (*questions)[question_id - 1].question_type;
-				let category = (*questions)[question_id - 1].category.clone();
-				let question = (*questions)[question_id - 1].question.clone();
-				let answers = (*questions)[question_id - 1].answers.clone();
-				//reset bets and question answers for all players
-				let mut player_access = game.player_data.write().await;
-				for player in (*player_access).iter_mut() {
-					//change zeros to None when using Options
-					player.money_bet = 0;
-					player.vs_player = "".to_owned();
-					player.answer = 0;
-				}
-				//create player update event (not for state transition yet)
-				let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
-					player_data: make_public_player_data(&player_access),
-				});
-				game.game_events.write().await.add(new_event);
-				//depending on question type begin different question-specific event
-				match question_type {
-					QuestionType::NormalQuestion => {
-						let new_event =
-							EventType::BeginNormalQAnswering(EventBeginNormalQAnswering {
-								question_type,
-								current_question: question_id,
-								category,
-								question,
-								answers,
-							});
-						game.game_events.write().await.add(new_event);
-						//set new question state
-						*state = LobbyState::NormalQAnswering(false);
-					}
-					QuestionType::BettingQuestion => {
-						let new_event =
-							EventType::BeginBettingQBetting(EventBeginBettingQBetting {
-								question_type,
-								current_question: question_id,
-								category,
-							});
-						game.game_events.write().await.add(new_event);
-						//set new question state
-						*state = LobbyState::BettingQBetting(false);
-					}
-					QuestionType::EstimationQuestion => {
-						let new_event =
-							EventType::BeginEstimationQAnswering(EventBeginEstimationQAnswering {
-								question_type,
-								current_question: question_id,
-								category,
-								question,
-							});
-						game.game_events.write().await.add(new_event);
-						//set new question state
-						*state = LobbyState::EstimationQAnswering(false);
-					}
-					QuestionType::VersusQuestion => {
-						let new_event =
-							EventType::BeginVersusQSelecting(EventBeginVersusQSelecting {
-								question_type,
-								current_question: question_id,
-								category,
-							});
-						game.game_events.write().await.add(new_event);
-						//set new question state
-						*state = LobbyState::VersusQSelecting(false);
-					}
+				begin_next_question(game, &mut state).await;
+			}
+		}
+		LobbyState::RoundSummary(true) => {
+			//transition to the next round's intro or the next question
+			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let starting_round = game
+				.rounds
+				.read()
+				.await
+				.iter()
+				.position(|round| round.first_question == question_id + 1);
+			if let Some(round) = starting_round {
+				add_round_intro(game, round).await;
+				*state = LobbyState::RoundIntro(false);
+			} else {
+				begin_next_question(game, &mut state).await;
+			}
+		}
+		LobbyState::Judging(true) => {
+			//score the question with the host's verdicts
+			*state = match game.judging.read().await.as_ref() {
+				Some(judging) => judging.scoring_state,
+				None => LobbyState::Results(false),
+			};
+			return false;
+		}
+		LobbyState::RoundIntro(true) => {
+			//transition to the round's first question
+			begin_next_question(game, &mut state).await;
+		}
+		LobbyState::BoardSelecting(true) => {
+			//transition to the picked question (the first one left if the admin skipped)
+			let num_questions = game.questions.read().await.len();
+			let question_id = {
+				let mut board_state = game.board.write().await;
+				let question_id = board_state
+					.selected
+					.take()
+					.or_else(|| board_state.first_available(num_questions));
+				if let Some(question_id) = question_id {
+					board_state.played.push(question_id);
 				}
+				question_id
+			};
+			match question_id {
+				Some(question_id) => begin_question(game, &mut state, question_id).await,
+				None => end_game(game, &mut state).await,
 			}
 		}
 		LobbyState::BettingQBetting(true) => {
 			//transition to answering state
 			//gather necessary data
 			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let language = game.language.read().await.clone();
 			let questions = game.questions.read().await;
-			let question = (*questions)[question_id - 1].question.clone();
-			let answers = (*questions)[question_id - 1].answers.clone();
+			let question = (*questions)[question_id - 1].question(&language, &language);
+			let answers = (*questions)[question_id - 1].answers(&language, &language);
+			let translations = (*questions)[question_id - 1]
+				.translations(&language)
+				.into_iter()
+				.map(|(lang, text)| (lang, text.without_category()))
+				.collect();
 			//create event
 			let new_event = EventType::BeginBettingQAnswering(EventBeginBettingQAnswering {
 				question,
 				answers,
+				translations,
 			});
 			game.game_events.write().await.add(new_event);
 			//set new question state
@@ -167,31 +292,213 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 			//transition to answering state
 			//gather necessary data
 			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let language = game.language.read().await.clone();
 			let questions = game.questions.read().await;
-			let question = (*questions)[question_id - 1].question.clone();
-			let answers = (*questions)[question_id - 1].answers.clone();
+			let question = (*questions)[question_id - 1].question(&language, &language);
+			let answers = (*questions)[question_id - 1].answers(&language, &language);
+			let translations = (*questions)[question_id - 1]
+				.translations(&language)
+				.into_iter()
+				.map(|(lang, text)| (lang, text.without_category()))
+				.collect();
 			//create event
-			let new_event =
-				EventType::BeginVersusQAnswering(EventBeginVersusQAnswering { question, answers });
+			let new_event = EventType::BeginVersusQAnswering(EventBeginVersusQAnswering {
+				question,
+				answers,
+				translations,
+			});
 			game.game_events.write().await.add(new_event);
 			//set new question state
 			*state = LobbyState::VersusQAnswering(false);
 		}
+		LobbyState::FinalQWagering(true) => {
+			//transition to answering state
+			//gather necessary data
+			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let language = game.language.read().await.clone();
+			let questions = game.questions.read().await;
+			let question = (*questions)[question_id - 1].question(&language, &language);
+			let answers = (*questions)[question_id - 1].answers(&language, &language);
+			let translations = (*questions)[question_id - 1]
+				.translations(&language)
+				.into_iter()
+				.map(|(lang, text)| (lang, text.without_category()))
+				.collect();
+			//create event
+			let new_event = EventType::BeginFinalQAnswering(EventBeginFinalQAnswering {
+				question,
+				answers,
+				translations,
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::FinalQAnswering(false);
+		}
+		LobbyState::FinalQAnswering(true) => {
+			//transition to the reveal, the results are revealed one player at a
+			// time, starting with the poorest player
+			let mut final_reveal = game.final_reveal.write().await;
+			let player_access = game.player_data.read().await;
+			let mut reveal_order: Vec<&PlayerData> =
+				(*player_access).iter().filter(|player| player.is_active()).collect();
+			reveal_order.sort_by_key(|player| player.money);
+			final_reveal.remaining =
+				reveal_order.iter().map(|player| player.uuid.clone()).collect();
+			final_reveal.previous_player_data = (*player_access).clone();
+			//create event
+			let new_event = EventType::BeginFinalQReveal(EventBeginFinalQReveal {
+				num_players: final_reveal.remaining.len(),
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::FinalQReveal(false);
+		}
+		LobbyState::FinalQReveal(true) => {
+			//reveal the next player or show the results after the last one
+			//gather necessary data
+			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let ruleset = game.ruleset.read().await.clone();
+			let questions = game.questions.read().await;
+			let correct_answer = (*questions)[question_id - 1].correct_answer;
+			let judging = game.judging.read().await;
+			let mut final_reveal = game.final_reveal.write().await;
+			let mut player_access = game.player_data.write().await;
+			let next_player = final_reveal
+				.remaining
+				.pop_front()
+				.and_then(|uuid| (*player_access).iter_mut().find(|player| player.uuid == uuid));
+			if let Some(player) = next_player {
+				//the wager is won or lost completely, without any money floor
+				let previous_money = player.money;
+				let wager = player.final_wager.unwrap_or(0);
+				let correct = judging::is_correct(judging.as_ref(), player, correct_answer);
+				if correct {
+					player.money = player.money.saturating_add(wager);
+					player.round_correct_answers += 1;
+				} else {
+					player.money = player.money.saturating_sub(wager);
+				}
+				//create event
+				let new_event = EventType::FinalQRevealPlayer(EventFinalQRevealPlayer {
+					name: player.name.clone(),
+					answer: player.answer,
+					correct,
+					wager,
+					previous_money,
+					money: player.money,
+				});
+				game.game_events.write().await.add(new_event);
+				//stay in reveal state for the next player
+				*state = LobbyState::FinalQReveal(false);
+			} else {
+				let eliminated = eliminate_players(&ruleset, &mut player_access, question_id);
+				//create event
+				let new_event = EventType::ShowResults(EventShowResults {
+					correct_answer,
+					previous_player_data: make_public_player_data(
+						&final_reveal.previous_player_data,
+					),
+					player_data: make_public_player_data(&player_access),
+				});
+				game.game_events.write().await.add(new_event);
+				if !eliminated.is_empty() {
+					let new_event =
+						EventType::PlayerEliminated(EventPlayerEliminated { eliminated });
+					game.game_events.write().await.add(new_event);
+				}
+				//create event for player update
+				let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+					player_data: make_public_player_data(&player_access),
+				});
+				game.game_events.write().await.add(new_event);
+				//set new question state
+				*state = LobbyState::Results(false);
+			}
+		}
 		LobbyState::NormalQAnswering(true) => {
 			//transition to results state
 			//gather necessary data
 			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let ruleset = game.ruleset.read().await.clone();
 			let questions = game.questions.read().await;
 			let correct_answer = (*questions)[question_id - 1].correct_answer;
+			let multiplier = round_multiplier(&game.rounds.read().await, question_id);
+			let judging = game.judging.read().await;
+			let ruleset = ruleset.with_question_value((*questions)[question_id - 1].value);
 			//compute the new money of each player
 			let mut player_access = game.player_data.write().await;
 			let previous_player_data = (*player_access).clone();
 			for player in (*player_access).iter_mut() {
-				if player.answer == correct_answer {
-					let normal_q_money = game.param_normal_q_money.load(atomic::Ordering::Relaxed);
-					player.money += normal_q_money;
+				if !player.is_active() || player.has_used_joker(JokerType::Skip) {
+					continue;
+				}
+				if judging::is_correct(judging.as_ref(), player, correct_answer) {
+					let won =
+						payout(ruleset.normal_q_result(true), player.payout_factor(), multiplier);
+					player.money = player.money.saturating_add(won);
+					player.round_correct_answers += 1;
+				} else {
+					let lost = ruleset.normal_q_result(false).saturating_mul(multiplier);
+					player.money = player.money.saturating_add(lost);
+				}
+				player.money = ruleset.limit_money(player.money);
+			}
+			//the audience poll ends with the question
+			*game.audience_poll.write().await = None;
+			let eliminated = eliminate_players(&ruleset, &mut player_access, question_id);
+			//create event
+			let new_event = EventType::ShowResults(EventShowResults {
+				correct_answer,
+				previous_player_data: make_public_player_data(&previous_player_data),
+				player_data: make_public_player_data(&player_access),
+			});
+			game.game_events.write().await.add(new_event);
+			if !eliminated.is_empty() {
+				let new_event = EventType::PlayerEliminated(EventPlayerEliminated { eliminated });
+				game.game_events.write().await.add(new_event);
+			}
+			//create event for player update
+			let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+				player_data: make_public_player_data(&player_access),
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::Results(false);
+		}
+		LobbyState::BuzzerQBuzzing(true) | LobbyState::BuzzerQAnswering(true) => {
+			//transition to results state (also if nobody buzzed or answered)
+			//gather necessary data
+			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let ruleset = game.ruleset.read().await.clone();
+			let wrong_penalty = game.buzzer_settings.read().await.wrong_penalty;
+			let questions = game.questions.read().await;
+			let correct_answer = (*questions)[question_id - 1].correct_answer;
+			let ruleset = ruleset.with_question_value((*questions)[question_id - 1].value);
+			let multiplier = round_multiplier(&game.rounds.read().await, question_id);
+			let mut buzzer_state = game.buzzer.write().await;
+			//compute the new money of each player, only the buzzing players are scored
+			let mut player_access = game.player_data.write().await;
+			let previous_player_data = (*player_access).clone();
+			for player in (*player_access).iter_mut() {
+				if !player.is_active() {
+					continue;
+				}
+				if buzzer_state.answering.as_ref() == Some(&player.uuid)
+					&& player.answer == correct_answer
+				{
+					let won =
+						payout(ruleset.normal_q_result(true), player.payout_factor(), multiplier);
+					player.money = player.money.saturating_add(won);
+					player.round_correct_answers += 1;
+				} else if buzzer_state.locked_out.contains(&player.uuid) {
+					player.money =
+						player.money.saturating_sub(wrong_penalty.saturating_mul(multiplier));
 				}
+				player.money = ruleset.limit_money(player.money);
 			}
+			buzzer_state.reset();
+			drop(buzzer_state);
+			let eliminated = eliminate_players(&ruleset, &mut player_access, question_id);
 			//create event
 			let new_event = EventType::ShowResults(EventShowResults {
 				correct_answer,
@@ -199,6 +506,10 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 				player_data: make_public_player_data(&player_access),
 			});
 			game.game_events.write().await.add(new_event);
+			if !eliminated.is_empty() {
+				let new_event = EventType::PlayerEliminated(EventPlayerEliminated { eliminated });
+				game.game_events.write().await.add(new_event);
+			}
 			//create event for player update
 			let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
 				player_data: make_public_player_data(&player_access),
@@ -211,22 +522,36 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 			//transition to results state
 			//gather necessary data
 			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let ruleset = game.ruleset.read().await.clone();
 			let questions = game.questions.read().await;
 			let correct_answer = (*questions)[question_id - 1].correct_answer;
+			let multiplier = round_multiplier(&game.rounds.read().await, question_id);
+			let judging = game.judging.read().await;
 			//compute the new money of each player
 			let mut player_access = game.player_data.write().await;
 			let previous_player_data = (*player_access).clone();
 			for player in (*player_access).iter_mut() {
-				if player.answer == correct_answer {
-					player.money += player.money_bet;
+				if !player.is_active() || player.has_used_joker(JokerType::Skip) {
+					continue;
+				}
+				if judging::is_correct(judging.as_ref(), player, correct_answer) {
+					let won = payout(
+						ruleset.betting_q_result(player.money_bet, true),
+						player.payout_factor(),
+						multiplier,
+					);
+					player.money = player.money.saturating_add(won);
+					player.round_correct_answers += 1;
 				} else {
-					player.money -= player.money_bet;
-					//if player has no money, give 1€ to allow continuing the game
-					if player.money == 0 {
-						player.money = 1;
-					}
+					let lost = ruleset
+						.betting_q_result(player.money_bet, false)
+						.saturating_mul(multiplier);
+					player.money = player.money.saturating_add(lost);
 				}
+				//if player has no money, give some to allow continuing the game
+				player.money = ruleset.limit_money(player.money);
 			}
+			let eliminated = eliminate_players(&ruleset, &mut player_access, question_id);
 			//create event
 			let new_event = EventType::ShowResults(EventShowResults {
 				correct_answer,
@@ -234,6 +559,10 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 				player_data: make_public_player_data(&player_access),
 			});
 			game.game_events.write().await.add(new_event);
+			if !eliminated.is_empty() {
+				let new_event = EventType::PlayerEliminated(EventPlayerEliminated { eliminated });
+				game.game_events.write().await.add(new_event);
+			}
 			//create event for player update
 			let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
 				player_data: make_public_player_data(&player_access),
@@ -246,19 +575,26 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 			//transition to results state
 			//gather necessary data
 			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let ruleset = game.ruleset.read().await.clone();
 			let questions = game.questions.read().await;
 			let correct_answer = (*questions)[question_id - 1].correct_answer;
+			let multiplier = round_multiplier(&game.rounds.read().await, question_id);
+			let judging = game.judging.read().await;
+			let ruleset = ruleset.with_question_value((*questions)[question_id - 1].value);
 			//compute the new money of each player
 			let mut closest_players = Vec::new();
 			let mut min_dinstance = usize::MAX;
 			let mut player_access = game.player_data.write().await;
 			let previous_player_data = (*player_access).clone();
 			for player in (*player_access).iter() {
-				let diff = if player.answer >= correct_answer {
-					player.answer - correct_answer
-				} else {
-					correct_answer - player.answer
-				};
+				//answers judged wrong never win, answers judged correct always win
+				if !player.is_active()
+					|| player.has_used_joker(JokerType::Skip)
+					|| judging::verdict(judging.as_ref(), player).is_some()
+				{
+					continue;
+				}
+				let diff = player.answer.abs_diff(correct_answer);
 				match diff.cmp(&min_dinstance) {
 					Ordering::Less => {
 						min_dinstance = diff;
@@ -268,13 +604,26 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 					_ => (),
 				}
 			}
+			for player in (*player_access).iter() {
+				if player.is_active()
+					&& !player.has_used_joker(JokerType::Skip)
+					&& judging::verdict(judging.as_ref(), player) == Some(true)
+				{
+					closest_players.push(player.name.clone());
+				}
+			}
 			for player in (*player_access).iter_mut() {
 				if closest_players.iter().any(|name| name == &player.name) {
-					let estimation_q_money =
-						game.param_estimation_q_money.load(atomic::Ordering::Relaxed);
-					player.money += estimation_q_money;
+					let won = payout(
+						ruleset.estimation_q_result(true),
+						player.payout_factor(),
+						multiplier,
+					);
+					player.money = player.money.saturating_add(won);
+					player.round_correct_answers += 1;
 				}
 			}
+			let eliminated = eliminate_players(&ruleset, &mut player_access, question_id);
 			//create event
 			let new_event = EventType::ShowResults(EventShowResults {
 				correct_answer,
@@ -282,6 +631,10 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 				player_data: make_public_player_data(&player_access),
 			});
 			game.game_events.write().await.add(new_event);
+			if !eliminated.is_empty() {
+				let new_event = EventType::PlayerEliminated(EventPlayerEliminated { eliminated });
+				game.game_events.write().await.add(new_event);
+			}
 			//create event for player update
 			let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
 				player_data: make_public_player_data(&player_access),
@@ -294,40 +647,45 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 			//transition to results state
 			//gather necessary data
 			let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+			let ruleset = game.ruleset.read().await.clone();
 			let questions = game.questions.read().await;
 			let correct_answer = (*questions)[question_id - 1].correct_answer;
+			let judging = game.judging.read().await;
 			//compute the new money of each player
 			let mut player_access = game.player_data.write().await;
 			let previous_player_data = (*player_access).clone();
 			let num_players = (*player_access).len();
 			let mut player_factors: Vec<f64> = vec![1.0; num_players];
 			for i in 0..num_players {
-				if (*player_access)[i].vs_player.is_empty() {
+				if (*player_access)[i].vs_player == 0
+					|| !(*player_access)[i].is_active()
+					|| (*player_access)[i].has_used_joker(JokerType::Skip)
+				{
 					continue;
 				}
+				let attacker_correct =
+					judging::is_correct(judging.as_ref(), &(*player_access)[i], correct_answer);
+				if attacker_correct {
+					(*player_access)[i].round_correct_answers += 1;
+				}
 				#[allow(clippy::needless_range_loop)]
 				for j in 0..num_players {
-					if (*player_access)[i].vs_player == (*player_access)[j].name {
-						if (*player_access)[i].answer == correct_answer {
-							//player_factors[i] *= 2.0;
-							player_factors[j] /= 2.0;
-						} else {
-							//player_factors[i] /= 2.0;
-							player_factors[j] *= 2.0;
-						}
+					if (*player_access)[i].vs_player == (*player_access)[j].id {
+						player_factors[j] *= ruleset.versus_factor(attacker_correct);
 						break;
 					}
 				}
 			}
 			#[allow(clippy::needless_range_loop)]
 			for i in 0..num_players {
-				(*player_access)[i].money =
-					((*player_access)[i].money as f64 * player_factors[i]) as i64;
-				//if player has no money, give 1€ to allow continuing the game
-				if (*player_access)[i].money == 0 {
-					(*player_access)[i].money = 1;
+				if !(*player_access)[i].is_active() {
+					continue;
 				}
+				let money = ((*player_access)[i].money as f64 * player_factors[i]) as i64;
+				//if player has no money, give some to allow continuing the game
+				(*player_access)[i].money = ruleset.limit_money(money);
 			}
+			let eliminated = eliminate_players(&ruleset, &mut player_access, question_id);
 			//create event
 			let new_event = EventType::ShowResults(EventShowResults {
 				correct_answer,
@@ -335,6 +693,10 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 				player_data: make_public_player_data(&player_access),
 			});
 			game.game_events.write().await.add(new_event);
+			if !eliminated.is_empty() {
+				let new_event = EventType::PlayerEliminated(EventPlayerEliminated { eliminated });
+				game.game_events.write().await.add(new_event);
+			}
 			//create event for player update
 			let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
 				player_data: make_public_player_data(&player_access),
@@ -344,7 +706,8 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 			*state = LobbyState::Results(false);
 		}
 		LobbyState::GameEnding(true) => {
-			//transition to menu state, no need to do anything else
+			//transition to menu state, a pause ends with the game
+			game.paused.store(false, atomic::Ordering::Relaxed);
 			//create event
 			let new_event =
 				EventType::BackToMenu(EventBackToMenu { open: *game.open.read().await });
@@ -361,7 +724,400 @@ pub async fn state_transition(game: &super::Gameshow) -> bool //returns if finis
 		| LobbyState::EstimationQAnswering(false)
 		| LobbyState::VersusQSelecting(false)
 		| LobbyState::VersusQAnswering(false)
-		| LobbyState::GameEnding(false) => {}
+		| LobbyState::GameEnding(false)
+		| LobbyState::RoundIntro(false)
+		| LobbyState::RoundSummary(false)
+		| LobbyState::FinalQWagering(false)
+		| LobbyState::FinalQAnswering(false)
+		| LobbyState::FinalQReveal(false)
+		| LobbyState::BoardSelecting(false)
+		| LobbyState::BuzzerQBuzzing(false)
+		| LobbyState::BuzzerQAnswering(false)
+		| LobbyState::Judging(false) => {}
 	}
 	true
 }
+
+//mark bankrupt players and the lowest-ranked players at checkpoints as
+// eliminated, returns the names of the newly eliminated players
+fn eliminate_players(
+	ruleset: &Ruleset,
+	players: &mut [PlayerData],
+	question_id: usize,
+) -> Vec<String> {
+	let mut eliminated = Vec::new();
+	if !ruleset.elimination {
+		return eliminated;
+	}
+
+	for player in players.iter_mut() {
+		if player.is_active() && player.money <= 0 {
+			player.eliminated = true;
+			eliminated.push(player.name.clone());
+		}
+	}
+
+	if ruleset.is_elimination_checkpoint(question_id) {
+		let mut ranking: Vec<&mut PlayerData> =
+			players.iter_mut().filter(|player| player.is_active()).collect();
+		ranking.sort_by_key(|player| player.money);
+		//keep at least one player in the game
+		let num_eliminations =
+			ruleset.eliminations_per_checkpoint.min(ranking.len().saturating_sub(1));
+		for player in ranking.into_iter().take(num_eliminations) {
+			player.eliminated = true;
+			eliminated.push(player.name.clone());
+		}
+	}
+
+	eliminated
+}
+
+//start another question than the next one in the sequence, returns false if
+// not possible
+pub async fn navigate(game: &super::Gameshow, navigation: Navigation) -> bool {
+	//ensure the game is running
+	let mut state = game.lobby_state.write().await;
+	if matches!(*state, LobbyState::Menu(_)) {
+		return false;
+	}
+	let from_question = game.current_question.load(atomic::Ordering::Relaxed);
+	let num_questions = game.questions.read().await.len();
+
+	//in board mode the skipped question stays played and the same chooser picks
+	// another one from the board
+	let board_mode = matches!(*game.game_mode.read().await, GameMode::Board(_));
+	if board_mode && navigation == Navigation::Skip {
+		{
+			let mut board_state = game.board.write().await;
+			if from_question > 0 && !board_state.played.contains(&from_question) {
+				board_state.played.push(from_question);
+			}
+		}
+		*game.final_reveal.write().await = FinalReveal::default();
+		let new_event = EventType::QuestionNavigation(EventQuestionNavigation {
+			navigation,
+			from_question,
+			to_question: 0,
+		});
+		game.game_events.write().await.add(new_event);
+		begin_board_selection(game, &mut state, None).await;
+		return true;
+	}
+
+	let to_question = match navigation {
+		Navigation::Skip => from_question + 1,
+		Navigation::Repeat => from_question.saturating_sub(1),
+		Navigation::Jump(question_id) => question_id,
+	};
+	let valid = match navigation {
+		Navigation::Skip => true,
+		Navigation::Repeat | Navigation::Jump(_) => (1..=num_questions).contains(&to_question),
+	};
+	if !valid {
+		return false;
+	}
+
+	//the question is played, whether picked from the board or not
+	{
+		let mut board_state = game.board.write().await;
+		if to_question <= num_questions && !board_state.played.contains(&to_question) {
+			board_state.played.push(to_question);
+		}
+	}
+	*game.final_reveal.write().await = FinalReveal::default();
+	//create event
+	let new_event = EventType::QuestionNavigation(EventQuestionNavigation {
+		navigation,
+		from_question,
+		to_question,
+	});
+	game.game_events.write().await.add(new_event);
+	//begin the question (resetting open answers) or end the game after the last
+	// one
+	if to_question > num_questions {
+		end_game(game, &mut state).await;
+	} else {
+		begin_question(game, &mut state, to_question).await;
+	}
+	true
+}
+
+//remember the game before scoring, dropping the oldest snapshot if there are
+// too many
+async fn take_snapshot(game: &super::Gameshow, lobby_state: LobbyState) {
+	let board = game.board.read().await.clone();
+	let mut snapshots = game.snapshots.write().await;
+	if snapshots.len() >= MAX_SNAPSHOTS {
+		snapshots.pop_front();
+	}
+	snapshots.push_back(GameSnapshot {
+		lobby_state,
+		current_question: game.current_question.load(atomic::Ordering::Relaxed),
+		board,
+		player_data: game.player_data.read().await.clone(),
+	});
+}
+
+//transition to next question or end the game after the last one
+async fn begin_next_question(game: &super::Gameshow, state: &mut LobbyState) {
+	let question_id = game.current_question.load(atomic::Ordering::Relaxed) + 1;
+	let num_questions = game.questions.read().await.len();
+	if question_id > num_questions {
+		end_game(game, state).await;
+	} else {
+		begin_question(game, state, question_id).await;
+	}
+}
+
+//let the chooser pick the next question from the board or end the game if all
+// questions were played; the current chooser picks again if no way to choose
+// the next one is given
+async fn begin_board_selection(
+	game: &super::Gameshow,
+	state: &mut LobbyState,
+	chooser: Option<BoardChooser>,
+) {
+	let question_id = game.current_question.load(atomic::Ordering::Relaxed);
+	let language = game.language.read().await.clone();
+	let questions = game.questions.read().await;
+	let mut board_state = game.board.write().await;
+	let player_access = game.player_data.read().await;
+	if let (Some(chooser), true) = (chooser, question_id > 0) {
+		let correct_players =
+			board::correct_players(&(*questions)[question_id - 1], &player_access);
+		board_state.next_chooser(chooser, &player_access, &correct_players);
+	}
+	if board_state.first_available((*questions).len()).is_none() {
+		drop(player_access);
+		drop(board_state);
+		drop(questions);
+		end_game(game, state).await;
+		return;
+	}
+	board_state.selected = None;
+	//create event
+	let new_event = EventType::BoardUpdate(EventBoardUpdate {
+		board: board::make_board(&questions, &board_state, &language),
+		chooser: board_state.chooser.as_ref().and_then(|uuid| {
+			player_access
+				.iter()
+				.find(|player| player.uuid == *uuid)
+				.map(|player| player.name.clone())
+		}),
+	});
+	game.game_events.write().await.add(new_event);
+	//set new question state
+	*state = LobbyState::BoardSelecting(false);
+}
+
+//game ending - create event
+async fn end_game(game: &super::Gameshow, state: &mut LobbyState) {
+	let new_event = EventType::GameEnding(EventGameEnding {
+		player_data: make_public_player_data(&game.player_data.read().await),
+	});
+	game.game_events.write().await.add(new_event);
+	//set new question state
+	*state = LobbyState::GameEnding(false);
+}
+
+//start a question (different states for different questions)
+async fn begin_question(game: &super::Gameshow, state: &mut LobbyState, question_id: usize) {
+	//gather necessary data
+	game.current_question.store(question_id, atomic::Ordering::Relaxed);
+	let buzzer_enabled = game.buzzer_settings.read().await.enabled;
+	let language = game.language.read().await.clone();
+	let questions = game.questions.read().await;
+	let question_type = (*questions)[question_id - 1].question_type;
+	let host_judges = (*questions)[question_id - 1].host_judges;
+	let category = (*questions)[question_id - 1].category(&language, &language);
+	let question = (*questions)[question_id - 1].question(&language, &language);
+	let answers = (*questions)[question_id - 1].answers(&language, &language);
+	let translations = (*questions)[question_id - 1].translations(&language);
+	game.buzzer.write().await.reset();
+	*game.judging.write().await = None;
+	//reset bets and question answers for all players
+	let mut player_access = game.player_data.write().await;
+	for player in (*player_access).iter_mut() {
+		//change zeros to None when using Options
+		player.money_bet = 0;
+		player.vs_player = 0;
+		player.answer = 0;
+		player.used_jokers.clear();
+		player.final_wager = None;
+	}
+	*game.audience_poll.write().await = None;
+	//create player update event (not for state transition yet)
+	let new_event = EventType::PlayerListUpdate(EventPlayerListUpdate {
+		player_data: make_public_player_data(&player_access),
+	});
+	game.game_events.write().await.add(new_event);
+	//depending on question type begin different question-specific event
+	match question_type {
+		QuestionType::NormalQuestion if buzzer_enabled && !host_judges => {
+			let new_event = EventType::BeginBuzzerQBuzzing(EventBeginBuzzerQBuzzing {
+				question_type,
+				current_question: question_id,
+				category,
+				question,
+				answers,
+				translations,
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::BuzzerQBuzzing(false);
+		}
+		QuestionType::NormalQuestion => {
+			let new_event = EventType::BeginNormalQAnswering(EventBeginNormalQAnswering {
+				question_type,
+				current_question: question_id,
+				category,
+				question,
+				answers,
+				translations,
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::NormalQAnswering(false);
+		}
+		QuestionType::BettingQuestion => {
+			let new_event = EventType::BeginBettingQBetting(EventBeginBettingQBetting {
+				question_type,
+				current_question: question_id,
+				category,
+				translations: translations
+					.into_iter()
+					.map(|(lang, text)| (lang, text.category_only()))
+					.collect(),
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::BettingQBetting(false);
+		}
+		QuestionType::EstimationQuestion => {
+			let new_event = EventType::BeginEstimationQAnswering(EventBeginEstimationQAnswering {
+				question_type,
+				current_question: question_id,
+				category,
+				question,
+				translations: translations
+					.into_iter()
+					.map(|(lang, text)| (lang, text.without_answers()))
+					.collect(),
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::EstimationQAnswering(false);
+		}
+		QuestionType::VersusQuestion => {
+			let new_event = EventType::BeginVersusQSelecting(EventBeginVersusQSelecting {
+				question_type,
+				current_question: question_id,
+				category,
+				translations: translations
+					.into_iter()
+					.map(|(lang, text)| (lang, text.category_only()))
+					.collect(),
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::VersusQSelecting(false);
+		}
+		QuestionType::FinalQuestion => {
+			let new_event = EventType::BeginFinalQWagering(EventBeginFinalQWagering {
+				question_type,
+				current_question: question_id,
+				category,
+				translations: translations
+					.into_iter()
+					.map(|(lang, text)| (lang, text.category_only()))
+					.collect(),
+			});
+			game.game_events.write().await.add(new_event);
+			//set new question state
+			*state = LobbyState::FinalQWagering(false);
+		}
+	}
+}
+
+//payout for a correct answer; money saturates instead of overflowing with huge
+// settings
+fn payout(money: i64, payout_factor: i64, multiplier: i64) -> i64 {
+	money.saturating_mul(payout_factor).saturating_mul(multiplier)
+}
+
+//payout multiplier of the round the question belongs to; it scales all money
+// won or lost, except in versus questions, which scale the money itself by a
+// factor, and final questions, where players choose their wager
+fn round_multiplier(rounds: &[RoundInfo], question_id: usize) -> i64 {
+	rounds.iter().find(|round| round.contains(question_id)).map_or(1, |round| round.multiplier)
+}
+
+//announce a round and start its statistics
+async fn add_round_intro(game: &super::Gameshow, round_index: usize) {
+	let language = game.language.read().await.clone();
+	let rounds = game.rounds.read().await;
+	let round = &(*rounds)[round_index];
+	let mut player_access = game.player_data.write().await;
+	for player in (*player_access).iter_mut() {
+		player.round_start_money = player.money;
+		player.round_correct_answers = 0;
+	}
+	//create event
+	let new_event = EventType::RoundIntro(EventRoundIntro {
+		round: round_index + 1,
+		num_rounds: (*rounds).len(),
+		name: round.name.resolve(&language, &language),
+		intro: round.intro.as_ref().map(|intro| intro.resolve(&language, &language)),
+		multiplier: round.multiplier,
+		num_questions: round.num_questions,
+		translations: round.translations(&language),
+	});
+	game.game_events.write().await.add(new_event);
+}
+
+//show the standings and statistics of a finished round
+async fn add_round_summary(game: &super::Gameshow, round_index: usize) {
+	let language = game.language.read().await.clone();
+	let rounds = game.rounds.read().await;
+	let round = &(*rounds)[round_index];
+	let mut standings = game.player_data.read().await.clone();
+	standings.sort_by_key(|player| Reverse(player.money));
+	let stats = standings
+		.iter()
+		.map(|player| RoundPlayerStats {
+			name: player.name.clone(),
+			money_change: player.money.saturating_sub(player.round_start_money),
+			correct_answers: player.round_correct_answers,
+		})
+		.collect();
+	//create event
+	let new_event = EventType::RoundSummary(EventRoundSummary {
+		round: round_index + 1,
+		num_rounds: (*rounds).len(),
+		name: round.name.resolve(&language, &language),
+		translations: round.translations(&language),
+		standings: make_public_player_data(&standings),
+		stats,
+	});
+	game.game_events.write().await.add(new_event);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn payout_multiplies() {
+		assert_eq!(payout(500, 1, 1), 500);
+		assert_eq!(payout(500, 2, 3), 3000);
+		assert_eq!(payout(-500, 2, 1), -1000);
+	}
+
+	#[test]
+	fn payout_saturates() {
+		assert_eq!(payout(i64::MAX, 2, 1), i64::MAX);
+		assert_eq!(payout(i64::MAX / 2 + 1, 1, 2), i64::MAX);
+		assert_eq!(payout(i64::MIN, 2, 2), i64::MIN);
+	}
+}
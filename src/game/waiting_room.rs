@@ -0,0 +1,38 @@
+use std::fmt;
+
+//player waiting for the admin to accept the join request
+pub struct JoinRequest {
+	pub uuid: String,
+	pub id: usize, //public player ID, kept when accepted
+	pub name: String,
+	pub language: String,
+}
+
+//successful join of a lobby, with the name the player got
+#[derive(Debug, Clone, PartialEq)]
+pub enum JoinResult {
+	Joined(String),
+	Pending(String), //the admin needs to accept the join request first
+}
+
+//reasons a player can not join
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum JoinError {
+	Banned,
+	Closed,
+	Full,
+	RequestNotFound,
+}
+
+impl fmt::Display for JoinError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			JoinError::Banned => write!(f, "You are banned!"),
+			JoinError::Closed => write!(f, "Lobby is closed!"),
+			JoinError::Full => write!(f, "Lobby is full!"),
+			JoinError::RequestNotFound => write!(f, "Join request not found"),
+		}
+	}
+}
+
+impl std::error::Error for JoinError {}
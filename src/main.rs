@@ -1,4 +1,4 @@
-use datahandler::DataHandler;
+use datahandler::{DataHandler, QuestionLibrary};
 use dotenvy::dotenv;
 use gameshow_v2::*;
 
@@ -7,7 +7,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 	dotenv().ok();
 
 	let db = DataHandler::new();
-	db.set_question_sets(game::find_question_files()?).await?;
+	db.set_question_sets(game::find_question_sets()?).await?;
+	db.set_question_library(QuestionLibrary::load()?).await?;
 
 	webserver::startup(db).await?;
 
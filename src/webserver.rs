@@ -1,4 +1,9 @@
-use std::env;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+	env, fs,
+	io::{self, Write},
+};
 
 use actix_session::{
 	config::CookieContentSecurity, storage::CookieSessionStore, SessionMiddleware,
@@ -17,7 +22,7 @@ const MAX_JSON_PAYLOAD: usize = 51200; //50 kiB
 pub async fn startup(db: DataHandler) -> std::io::Result<()> {
 	let app_db = web::Data::new(db);
 	let json_config = web::JsonConfig::default().limit(MAX_JSON_PAYLOAD);
-	let cookie_key = Key::generate();
+	let cookie_key = load_cookie_key()?;
 
 	HttpServer::new(move || {
 		App::new()
@@ -48,6 +53,39 @@ pub async fn startup(db: DataHandler) -> std::io::Result<()> {
 	.await
 }
 
+//key to encrypt the session cookies with; loaded from COOKIE_KEY_FILE (and
+// created there if missing) so sessions survive restarts, new on every start
+// otherwise
+fn load_cookie_key() -> io::Result<Key> {
+	let path = match env::var("COOKIE_KEY_FILE") {
+		Ok(path) if !path.is_empty() => path,
+		_ => return Ok(Key::generate()),
+	};
+	match fs::read(&path) {
+		Ok(bytes) => Key::try_from(bytes.as_slice()).map_err(|_err| {
+			io::Error::new(io::ErrorKind::InvalidData, "Cookie key needs at least 64 bytes!")
+		}),
+		Err(err) if err.kind() == io::ErrorKind::NotFound => {
+			//the key is secret, so only the owner may read it
+			let key = Key::generate();
+			let mut options = fs::OpenOptions::new();
+			options.write(true).create_new(true);
+			#[cfg(unix)]
+			options.mode(0o600);
+			options.open(&path)?.write_all(key.master())?;
+			Ok(key)
+		}
+		Err(err) => Err(err),
+	}
+}
+
+//password for a protected lobby, sent as header to keep it out of URLs and
+// logs
+fn get_lobby_password(request: &HttpRequest) -> Option<&str> {
+	let password = request.headers().get("Lobby-Password")?;
+	std::str::from_utf8(password.as_bytes()).ok()
+}
+
 fn ensure_cookie_consent(request: &HttpRequest) -> HttpResult<()> {
 	let consent_cookie = request.cookie("CONSENT");
 	if let Some(consent) = consent_cookie {
@@ -1,19 +1,24 @@
 use std::env;
 
 use actix_session::Session;
-use actix_web::{error, get, web, HttpRequest, HttpResponse, Result as HttpResult};
+use actix_web::{error, web, HttpRequest, HttpResponse, Result as HttpResult};
 use serde::{Deserialize, Serialize};
 
 use super::ensure_cookie_consent;
 use crate::datahandler::DataHandler;
 
+mod library;
 mod lobby;
+mod v1;
+mod v2;
 
 const MAX_NICKNAME_LENGTH: usize = 25;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-	cfg.service(set_name).service(get_name).service(get_question_sets);
-	lobby::config(cfg);
+	cfg.service(web::scope("/v2").configure(v2::config).configure(lobby::v2::config));
+	v1::config(cfg);
+	lobby::v1::config(cfg);
+	library::config(cfg);
 }
 
 // Set the current user's name; if not logged in, create new user
@@ -21,14 +26,13 @@ pub fn config(cfg: &mut web::ServiceConfig) {
 struct SetNameData {
 	name: String,
 }
-#[get("/set_name")]
 async fn set_name(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Query<SetNameData>,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: SetNameData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	//get maximum name length
 	let max_nickname_length =
@@ -44,13 +48,31 @@ async fn set_name(
 
 	//check if user exists before and possible change name only
 	if let Some(uuid) = session.get::<String>("uuid")? {
+		//the new name is taken over by all lobbies of the player, unless one of
+		// them is running a game with locked names
+		let lobbies =
+			db.get_player_lobbies(&uuid).await.map_err(error::ErrorInternalServerError)?;
+		for lobby in lobbies.iter() {
+			if !lobby.may_rename().await {
+				return Err(error::ErrorConflict("Names are locked while the game is running!"));
+			}
+		}
 		let was_there = db
-			.set_player_name(uuid, params.name.clone())
+			.set_player_name(uuid.clone(), params.name.clone())
 			.await
 			.map_err(error::ErrorInternalServerError)?;
+		for lobby in lobbies.iter() {
+			lobby.rename(&uuid, params.name.clone()).await;
+		}
 		if was_there {
 			Ok(HttpResponse::Ok().json(params.name.clone()))
 		} else {
+			//the session outlived the player, e.g. with a persistent cookie key
+			let uuid = db
+				.create_player(params.name.clone())
+				.await
+				.map_err(error::ErrorInternalServerError)?;
+			session.insert("uuid", uuid)?;
 			Ok(HttpResponse::Created().json(params.name.clone()))
 		}
 	} else {
@@ -63,18 +85,17 @@ async fn set_name(
 
 // Get current user's name (for after reloading page, can be used to check if
 // "logged in")
-#[get("/get_name")]
 async fn get_name(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let name = db.get_player_name(uuid).await.map_err(error::ErrorInternalServerError)?;
-		if name.is_some() {
-			Ok(HttpResponse::Ok().json(name.unwrap()))
+		if let Some(name) = name {
+			Ok(HttpResponse::Ok().json(name))
 		} else {
 			Err(error::ErrorNotFound("Invalid UUID: Player UUID not found in database!"))
 		}
@@ -84,9 +105,7 @@ async fn get_name(
 }
 
 // Get a list of question sets
-#[get("/get_question_sets")]
-async fn get_question_sets(db: web::Data<DataHandler>) -> HttpResult<HttpResponse> {
-	let list = db.get_question_sets().await.map_err(error::ErrorInternalServerError)?;
-	let question_sets: Vec<String> = list.iter().map(|x| x.0.clone()).collect();
+async fn get_question_sets(db: &DataHandler) -> HttpResult<HttpResponse> {
+	let question_sets = db.get_question_sets().await.map_err(error::ErrorInternalServerError)?;
 	Ok(HttpResponse::Ok().json(question_sets))
 }
@@ -0,0 +1,301 @@
+use std::env;
+
+use actix_web::{error, get, post, web, HttpRequest, HttpResponse, Result as HttpResult};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	datahandler::{DataHandler, LibraryEdit, LibraryError},
+	game::{Question, QuestionSetMeta},
+};
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+	cfg.service(get_sets)
+		.service(get_set)
+		.service(get_revisions)
+		.service(create_set)
+		.service(update_set)
+		.service(delete_set)
+		.service(duplicate_set)
+		.service(add_question)
+		.service(update_question)
+		.service(delete_question)
+		.service(duplicate_question);
+}
+
+// The library is only accessible with the token configured on the server
+fn ensure_library_access(request: &HttpRequest) -> HttpResult<()> {
+	let token = env::var("QUESTION_LIBRARY_TOKEN").unwrap_or_default();
+	if token.is_empty() {
+		return Err(error::ErrorForbidden("Question library is disabled on this server!"));
+	}
+
+	let expected = format!("Bearer {}", token);
+	let authorization = request.headers().get("Authorization").map(|h| h.as_bytes());
+	let authorization = authorization.unwrap_or_default();
+	//compare all bytes so the time taken does not depend on the token
+	let matches = authorization.len() == expected.len()
+		&& authorization.iter().zip(expected.as_bytes()).fold(0, |diff, (a, b)| diff | (a ^ b))
+			== 0;
+	if matches {
+		Ok(())
+	} else {
+		Err(error::ErrorUnauthorized("Invalid question library token!"))
+	}
+}
+
+fn library_error(err: LibraryError) -> error::Error {
+	let msg = err.to_string();
+	match err {
+		LibraryError::NotFound => error::ErrorNotFound(msg),
+		LibraryError::AlreadyExists => error::ErrorConflict(msg),
+		LibraryError::InvalidName | LibraryError::InvalidIndex => error::ErrorBadRequest(msg),
+		LibraryError::Io(_) => error::ErrorInternalServerError(msg),
+	}
+}
+
+// List the latest revisions of all library question sets
+#[get("/library/get_sets")]
+async fn get_sets(db: web::Data<DataHandler>, request: HttpRequest) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let sets = db.get_library_sets().await.map_err(error::ErrorInternalServerError)?;
+	Ok(HttpResponse::Ok().json(sets))
+}
+
+// Get a question set with its questions (latest revision if none is given)
+#[derive(Serialize, Deserialize)]
+struct GetSetData {
+	name: String,
+	revision: Option<usize>,
+}
+#[get("/library/get_set")]
+async fn get_set(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Query<GetSetData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let set = db
+		.get_library_revision(params.name.clone(), params.revision)
+		.await
+		.map_err(error::ErrorInternalServerError)?;
+	if let Some(set) = set {
+		Ok(HttpResponse::Ok().json(set))
+	} else {
+		Err(library_error(LibraryError::NotFound))
+	}
+}
+
+// Get the revision history of a question set
+#[derive(Serialize, Deserialize)]
+struct GetRevisionsData {
+	name: String,
+}
+#[get("/library/get_revisions")]
+async fn get_revisions(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Query<GetRevisionsData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let revisions = db
+		.get_library_revisions(params.name.clone())
+		.await
+		.map_err(error::ErrorInternalServerError)?;
+	if let Some(revisions) = revisions {
+		Ok(HttpResponse::Ok().json(revisions))
+	} else {
+		Err(library_error(LibraryError::NotFound))
+	}
+}
+
+// Create a new question set
+#[derive(Serialize, Deserialize)]
+struct CreateSetData {
+	name: String,
+	#[serde(default)]
+	meta: QuestionSetMeta,
+	#[serde(default)]
+	questions: Vec<Question>,
+}
+#[post("/library/create_set")]
+async fn create_set(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Json<CreateSetData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let params = params.into_inner();
+	let set = db
+		.create_library_set(params.name, params.meta, params.questions)
+		.await
+		.map_err(error::ErrorInternalServerError)?
+		.map_err(library_error)?;
+	Ok(HttpResponse::Created().json(set))
+}
+
+// Replace metadata and questions of a question set
+#[derive(Serialize, Deserialize)]
+struct UpdateSetData {
+	name: String,
+	#[serde(default)]
+	meta: QuestionSetMeta,
+	questions: Vec<Question>,
+}
+#[post("/library/update_set")]
+async fn update_set(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Json<UpdateSetData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let params = params.into_inner();
+	let set = db
+		.edit_library_set(params.name, LibraryEdit::ReplaceAll(params.meta, params.questions))
+		.await
+		.map_err(error::ErrorInternalServerError)?
+		.map_err(library_error)?;
+	Ok(HttpResponse::Ok().json(set))
+}
+
+// Delete a question set with all revisions
+#[derive(Serialize, Deserialize)]
+struct DeleteSetData {
+	name: String,
+}
+#[post("/library/delete_set")]
+async fn delete_set(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Json<DeleteSetData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	db.delete_library_set(params.name.clone())
+		.await
+		.map_err(error::ErrorInternalServerError)?
+		.map_err(library_error)?;
+	Ok(HttpResponse::NoContent().finish())
+}
+
+// Copy a revision of a question set to a new set
+#[derive(Serialize, Deserialize)]
+struct DuplicateSetData {
+	name: String,
+	revision: Option<usize>,
+	new_name: String,
+}
+#[post("/library/duplicate_set")]
+async fn duplicate_set(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Json<DuplicateSetData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let params = params.into_inner();
+	let set = db
+		.duplicate_library_set(params.name, params.revision, params.new_name)
+		.await
+		.map_err(error::ErrorInternalServerError)?
+		.map_err(library_error)?;
+	Ok(HttpResponse::Created().json(set))
+}
+
+// Add a question to a question set (at the end if no position is given)
+#[derive(Serialize, Deserialize)]
+struct AddQuestionData {
+	name: String,
+	position: Option<usize>,
+	question: Question,
+}
+#[post("/library/add_question")]
+async fn add_question(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Json<AddQuestionData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let params = params.into_inner();
+	let set = db
+		.edit_library_set(params.name, LibraryEdit::AddQuestion(params.position, params.question))
+		.await
+		.map_err(error::ErrorInternalServerError)?
+		.map_err(library_error)?;
+	Ok(HttpResponse::Ok().json(set))
+}
+
+// Replace a question of a question set
+#[derive(Serialize, Deserialize)]
+struct UpdateQuestionData {
+	name: String,
+	index: usize,
+	question: Question,
+}
+#[post("/library/update_question")]
+async fn update_question(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Json<UpdateQuestionData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let params = params.into_inner();
+	let set = db
+		.edit_library_set(params.name, LibraryEdit::UpdateQuestion(params.index, params.question))
+		.await
+		.map_err(error::ErrorInternalServerError)?
+		.map_err(library_error)?;
+	Ok(HttpResponse::Ok().json(set))
+}
+
+// Remove a question from a question set
+#[derive(Serialize, Deserialize)]
+struct DeleteQuestionData {
+	name: String,
+	index: usize,
+}
+#[post("/library/delete_question")]
+async fn delete_question(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Json<DeleteQuestionData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let params = params.into_inner();
+	let set = db
+		.edit_library_set(params.name, LibraryEdit::DeleteQuestion(params.index))
+		.await
+		.map_err(error::ErrorInternalServerError)?
+		.map_err(library_error)?;
+	Ok(HttpResponse::Ok().json(set))
+}
+
+// Insert a copy of a question right after it
+#[derive(Serialize, Deserialize)]
+struct DuplicateQuestionData {
+	name: String,
+	index: usize,
+}
+#[post("/library/duplicate_question")]
+async fn duplicate_question(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Json<DuplicateQuestionData>,
+) -> HttpResult<HttpResponse> {
+	ensure_library_access(&request)?;
+
+	let params = params.into_inner();
+	let set = db
+		.edit_library_set(params.name, LibraryEdit::DuplicateQuestion(params.index))
+		.await
+		.map_err(error::ErrorInternalServerError)?
+		.map_err(library_error)?;
+	Ok(HttpResponse::Ok().json(set))
+}
@@ -1,47 +1,44 @@
+use std::{collections::BTreeMap, env, io, time::Duration};
+
 use actix_session::Session;
-use actix_web::{error, get, post, web, HttpRequest, HttpResponse, Result as HttpResult};
+use actix_web::{error, HttpRequest, HttpResponse, Result as HttpResult};
 use futures::join;
 use serde::{Deserialize, Serialize};
 
 use super::ensure_cookie_consent;
-use crate::{datahandler::DataHandler, game};
-
-pub fn config(cfg: &mut web::ServiceConfig) {
-	cfg.service(create_lobby)
-		.service(join_lobby)
-		.service(leave_lobby)
-		.service(get_events)
-		.service(get_player_data)
-		.service(update_lobby)
-		.service(upload_custom_questions)
-		.service(kick_player)
-		.service(set_player_attributes)
-		.service(next_state)
-		.service(bet_money)
-		.service(attack_player)
-		.service(answer_question)
-		.service(get_joker);
-}
+use crate::{
+	datahandler::{DataHandler, LobbyFilter, MAX_PAGE_SIZE},
+	game,
+	webserver::get_lobby_password,
+};
+
+pub mod v1;
+pub mod v2;
+
+const MAX_JOIN_CODE_VALIDITY: u64 = 60 * 60 * 24 * 365; //seconds, one year
 
 // Create a new lobby without joining
-#[get("/create_lobby")]
 async fn create_lobby(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let name =
 			db.get_player_name(uuid.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if name.is_some() {
-			let name = name.unwrap();
+		if let Some(name) = name {
 			let lobby_uuid = db
 				.create_lobby(uuid, name.clone())
 				.await
 				.map_err(error::ErrorInternalServerError)?;
-			Ok(HttpResponse::Ok().json((lobby_uuid, name)))
+			let join_code = db
+				.get_join_code(lobby_uuid.clone())
+				.await
+				.map_err(error::ErrorInternalServerError)?
+				.map(|join_code| join_code.join_code);
+			Ok(HttpResponse::Ok().json((lobby_uuid, name, join_code)))
 		} else {
 			Err(error::ErrorNotFound("Invalid UUID: Player UUID not found in database!"))
 		}
@@ -50,43 +47,116 @@ async fn create_lobby(
 	}
 }
 
-// Join an existing lobby
+// List the open lobbies their admins chose to show publicly
+#[derive(Serialize, Deserialize)]
+struct ListLobbiesData {
+	search: Option<String>,
+	language: Option<String>,
+	phase: Option<game::LobbyPhase>,
+	without_password: Option<bool>,
+	page: Option<usize>,
+	page_size: Option<usize>,
+}
+async fn list_lobbies(
+	db: &DataHandler,
+	request: &HttpRequest,
+	params: ListLobbiesData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	let filter = LobbyFilter {
+		search: params.search,
+		language: params.language,
+		phase: params.phase,
+		without_password: params.without_password.unwrap_or(false),
+	};
+	let page = params.page.unwrap_or(0);
+	let page_size = params.page_size.unwrap_or(MAX_PAGE_SIZE);
+	let lobbies =
+		db.list_lobbies(&filter, page, page_size).await.map_err(error::ErrorInternalServerError)?;
+	Ok(HttpResponse::Ok().json(lobbies))
+}
+
+// Join an existing lobby, the password of a protected lobby is sent in the
+// Lobby-Password header
 #[derive(Serialize, Deserialize)]
 struct JoinLobbyData {
 	uuid: String,
+	language: Option<String>,
 }
 #[derive(Serialize, Deserialize)]
 struct JoinLobbyReturn {
+	lobby_id: String, //the lobby's UUID, also when joining by join code
 	admin: String,
 	new_name: String,
+	player_id: Option<usize>, //public ID of the player, none while waiting for approval
+	pending: bool,            //waiting for the admin to accept the join request
 }
-#[get("/join_lobby")]
 async fn join_lobby(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Query<JoinLobbyData>,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: JoinLobbyData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let player_name =
 			db.get_player_name(uuid.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if player_name.is_some() {
-			let db_lobby =
-				db.get_lobby(params.uuid.clone()).await.map_err(error::ErrorInternalServerError)?;
-			if db_lobby.is_some() {
-				let lobby = db_lobby.unwrap();
+		if let Some(player_name) = player_name {
+			let lobby_id = db
+				.get_lobby_id(params.uuid.clone())
+				.await
+				.map_err(error::ErrorInternalServerError)?;
+			let db_lobby = match &lobby_id {
+				Some(lobby_id) => {
+					db.get_lobby(lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?
+				}
+				None => None,
+			};
+			if let (Some(lobby_id), Some(lobby)) = (lobby_id, db_lobby) {
+				if let Some(language) = &params.language {
+					if !game::is_valid_language_code(language) {
+						return Err(error::ErrorBadRequest("Invalid language code!"));
+					}
+				}
+				if !lobby.may_watch(Some(&uuid), get_lobby_password(request)).await {
+					return Err(error::ErrorForbidden("Could not join lobby: Wrong password!"));
+				}
 				let admin_name = lobby.get_admin_name().await;
 				//finally do the joining itself
-				let joined_name = lobby.join(&uuid, player_name.unwrap()).await;
-				if joined_name.is_some() {
-					Ok(HttpResponse::Ok().json(JoinLobbyReturn {
-						admin: admin_name,
-						new_name: joined_name.unwrap(),
-					}))
-				} else {
-					Err(error::ErrorForbidden("Could not join lobby: Lobby is closed!"))
+				let res = lobby.join(&uuid, player_name, params.language.clone()).await;
+				match res {
+					Ok(game::JoinResult::Joined(joined_name)) => {
+						Ok(HttpResponse::Ok().json(JoinLobbyReturn {
+							lobby_id,
+							admin: admin_name,
+							new_name: joined_name,
+							player_id: lobby.get_player_id(&uuid).await,
+							pending: false,
+						}))
+					}
+					//the player is told about the admin's decision by a private event
+					Ok(game::JoinResult::Pending(joined_name)) => Ok(HttpResponse::Accepted()
+						.json(JoinLobbyReturn {
+							lobby_id,
+							admin: admin_name,
+							new_name: joined_name,
+							player_id: None,
+							pending: true,
+						})),
+					Err(game::JoinError::Banned) => {
+						Err(error::ErrorForbidden("Could not join lobby: You are banned!"))
+					}
+					Err(game::JoinError::Closed) => {
+						Err(error::ErrorForbidden("Could not join lobby: Lobby is closed!"))
+					}
+					Err(game::JoinError::Full) => {
+						Err(error::ErrorConflict("Could not join lobby: Lobby is full!"))
+					}
+					Err(game::JoinError::RequestNotFound) => {
+						Err(error::ErrorInternalServerError("Join request got lost!"))
+					}
 				}
 			} else {
 				Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
@@ -99,25 +169,235 @@ async fn join_lobby(
 	}
 }
 
+// Get the short code to join the lobby with
+#[derive(Serialize, Deserialize)]
+struct GetJoinCodeData {
+	lobby_id: String,
+}
+async fn get_join_code(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: GetJoinCodeData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let lobby_id = db
+			.get_lobby_id(params.lobby_id.clone())
+			.await
+			.map_err(error::ErrorInternalServerError)?;
+		let db_lobby = match &lobby_id {
+			Some(lobby_id) => {
+				db.get_lobby(lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?
+			}
+			None => None,
+		};
+		if let (Some(lobby_id), Some(lobby)) = (lobby_id, db_lobby) {
+			if lobby.get_admin_uuid().await != uuid && !lobby.is_joined(&uuid).await {
+				return Err(error::ErrorUnauthorized("You are not in the lobby!"));
+			}
+			let join_code =
+				db.get_join_code(lobby_id).await.map_err(error::ErrorInternalServerError)?;
+			if let Some(join_code) = join_code {
+				Ok(HttpResponse::Ok().json(join_code))
+			} else {
+				Err(error::ErrorNotFound("The join code expired!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Replace the join code, e.g. with one that expires after a number of seconds
+#[derive(Serialize, Deserialize)]
+struct RegenerateJoinCodeData {
+	lobby_id: String,
+	valid_for: Option<u64>,
+}
+async fn regenerate_join_code(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: RegenerateJoinCodeData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let lobby_id = db
+			.get_lobby_id(params.lobby_id.clone())
+			.await
+			.map_err(error::ErrorInternalServerError)?;
+		let db_lobby = match &lobby_id {
+			Some(lobby_id) => {
+				db.get_lobby(lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?
+			}
+			None => None,
+		};
+		if let (Some(lobby_id), Some(lobby)) = (lobby_id, db_lobby) {
+			if lobby.get_admin_uuid().await != uuid {
+				return Err(error::ErrorUnauthorized("You are not the lobby admin!"));
+			}
+			if params.valid_for.is_some_and(|valid_for| valid_for > MAX_JOIN_CODE_VALIDITY) {
+				return Err(error::ErrorBadRequest(format!(
+					"Join code validity is too long! Maximum is {} seconds!",
+					MAX_JOIN_CODE_VALIDITY
+				)));
+			}
+			let valid_for = params.valid_for.map(Duration::from_secs);
+			let join_code = db
+				.regenerate_join_code(lobby_id, valid_for)
+				.await
+				.map_err(error::ErrorInternalServerError)?;
+			if let Some(join_code) = join_code {
+				Ok(HttpResponse::Ok().json(join_code))
+			} else {
+				Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Get a token to continue as the current player on another device
+#[derive(Serialize, Deserialize)]
+struct CreateReconnectTokenData {
+	lobby_id: String,
+}
+async fn create_reconnect_token(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: CreateReconnectTokenData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			let token = lobby.create_reconnect_token(&uuid).await;
+			if let Some(token) = token {
+				Ok(HttpResponse::Ok().json(token))
+			} else {
+				Err(error::ErrorUnauthorized("You are not in the lobby!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Continue as the player a reconnect token was created for, the other device
+// loses access to the player
+#[derive(Serialize, Deserialize)]
+struct RedeemReconnectTokenData {
+	lobby_id: String,
+	token: String,
+}
+async fn redeem_reconnect_token(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: RedeemReconnectTokenData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	let lobby_id =
+		db.get_lobby_id(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+	let db_lobby = match &lobby_id {
+		Some(lobby_id) => {
+			db.get_lobby(lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?
+		}
+		None => None,
+	};
+	if let (Some(lobby_id), Some(lobby)) = (lobby_id, db_lobby) {
+		if !lobby.is_valid_reconnect_token(&params.token).await {
+			return Err(error::ErrorForbidden("Reconnect token is invalid!"));
+		}
+		//devices without a valid session get a new player, named after the
+		// player they continue as
+		let mut uuid = session.get::<String>("uuid")?;
+		if let Some(session_uuid) = &uuid {
+			let player_name = db
+				.get_player_name(session_uuid.clone())
+				.await
+				.map_err(error::ErrorInternalServerError)?;
+			if player_name.is_none() {
+				uuid = None;
+			}
+		}
+		let new_player = uuid.is_none();
+		let uuid = match uuid {
+			Some(uuid) => uuid,
+			None => {
+				let uuid = db
+					.create_player(String::new())
+					.await
+					.map_err(error::ErrorInternalServerError)?;
+				session.insert("uuid", uuid.clone())?;
+				uuid
+			}
+		};
+
+		match lobby.redeem_reconnect_token(&params.token, &uuid).await {
+			Ok(()) => {
+				let admin_name = lobby.get_admin_name().await;
+				let player_id = lobby.get_player_id(&uuid).await;
+				let name = match lobby.get_player_name(&uuid).await {
+					Some(name) => name,
+					None => admin_name.clone(), //the admin does not need to play
+				};
+				if new_player {
+					db.set_player_name(uuid, name.clone())
+						.await
+						.map_err(error::ErrorInternalServerError)?;
+				}
+				Ok(HttpResponse::Ok().json(JoinLobbyReturn {
+					lobby_id,
+					admin: admin_name,
+					new_name: name,
+					player_id,
+					pending: false,
+				}))
+			}
+			Err(game::ReconnectError::InvalidToken) => {
+				Err(error::ErrorForbidden("Reconnect token is invalid!"))
+			}
+			Err(game::ReconnectError::AlreadyJoined) => {
+				Err(error::ErrorConflict("You already joined this lobby!"))
+			}
+		}
+	} else {
+		Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+	}
+}
+
 // Leave a lobby
 #[derive(Serialize, Deserialize)]
 struct LeaveLobbyData {
 	uuid: String,
 }
-#[get("/leave_lobby")]
 async fn leave_lobby(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Query<LeaveLobbyData>,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: LeaveLobbyData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.uuid.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
+		if let Some(lobby) = db_lobby {
 			if lobby.leave(&uuid).await {
 				Ok(HttpResponse::NoContent().finish())
 			} else {
@@ -131,23 +411,26 @@ async fn leave_lobby(
 	}
 }
 
-// Get a lobby's events
+// Get a lobby's events (Lobby-Password header for protected lobbies)
 #[derive(Serialize, Deserialize)]
 struct GetEventsData {
 	lobby_id: String,
 }
-#[get("/get_events")]
 async fn get_events(
-	db: web::Data<DataHandler>,
-	request: HttpRequest,
-	params: web::Query<GetEventsData>,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: GetEventsData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
+	let uuid = session.get::<String>("uuid")?;
 	let db_lobby =
 		db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-	if db_lobby.is_some() {
-		let lobby = db_lobby.unwrap();
+	if let Some(lobby) = db_lobby {
+		if !lobby.may_watch(uuid.as_deref(), get_lobby_password(request)).await {
+			return Err(error::ErrorUnauthorized("Wrong lobby password!"));
+		}
 		Ok(HttpResponse::Ok()
 			.append_header(("Cache-Control", "no-cache"))
 			.json(lobby.get_events().await))
@@ -156,23 +439,50 @@ async fn get_events(
 	}
 }
 
-// Get a lobby's player data
+// Get the events only addressed to the current player
+async fn get_private_events(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: GetEventsData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			Ok(HttpResponse::Ok()
+				.append_header(("Cache-Control", "no-cache"))
+				.json(lobby.get_private_events(&uuid).await))
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Get a lobby's player data (Lobby-Password header for protected lobbies)
 #[derive(Serialize, Deserialize)]
 struct GetPlayerDataData {
 	lobby_id: String,
 }
-#[get("/get_player_data")]
 async fn get_player_data(
-	db: web::Data<DataHandler>,
-	request: HttpRequest,
-	params: web::Query<GetPlayerDataData>,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: GetPlayerDataData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
+	let uuid = session.get::<String>("uuid")?;
 	let db_lobby =
 		db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-	if db_lobby.is_some() {
-		let lobby = db_lobby.unwrap();
+	if let Some(lobby) = db_lobby {
+		if !lobby.may_watch(uuid.as_deref(), get_lobby_password(request)).await {
+			return Err(error::ErrorUnauthorized("Wrong lobby password!"));
+		}
 		return Ok(HttpResponse::Ok()
 			.append_header(("Cache-Control", "no-cache"))
 			.json(lobby.get_player_data().await));
@@ -185,28 +495,106 @@ async fn get_player_data(
 #[derive(Serialize, Deserialize)]
 struct UpdateLobbyData {
 	lobby_id: String,
+	#[serde(flatten)]
+	settings: LobbySettingsData,
+}
+#[derive(Serialize, Deserialize)]
+struct LobbySettingsData {
 	open: bool,
+	listed: Option<bool>,
+	max_players: Option<usize>, //0 for no limit
+	approval_required: Option<bool>,
+	lock_renames: Option<bool>, //keep names while a game is running
+	password: Option<String>,   //new password, empty to remove it, unchanged if none
 	initial_money: i64,
 	initial_jokers: usize,
 	normal_q_money: i64,
 	estimation_q_money: i64,
 	question_set: String,
+	question_set_revision: Option<usize>,
+	language: Option<String>,
+	special_jokers: Option<BTreeMap<game::JokerType, usize>>,
+	audience_poll_includes_players: Option<bool>,
+	ruleset: Option<game::Ruleset>,
+	ruleset_preset: Option<game::RulesetPreset>,
+	game_mode: Option<game::GameMode>,
+	buzzer: Option<game::BuzzerSettings>,
 }
-#[post("/update_lobby")]
 async fn update_lobby(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Json<UpdateLobbyData>,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: UpdateLobbyData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
+	let UpdateLobbyData { lobby_id, settings: params } = params;
 	if let Some(uuid) = session.get::<String>("uuid")? {
-		let db_lobby =
-			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
+		let db_lobby = db.get_lobby(lobby_id).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
 			if lobby.get_admin_uuid().await == uuid {
+				//an explicit ruleset overrides the preset and the single money parameters
+				let ruleset = params
+					.ruleset
+					.clone()
+					.or_else(|| params.ruleset_preset.map(|preset| preset.ruleset()));
+				if ruleset.as_ref().is_some_and(|ruleset| !ruleset.is_valid()) {
+					return Err(error::ErrorBadRequest("Invalid ruleset!"));
+				}
+				if params.buzzer.as_ref().is_some_and(|buzzer| !buzzer.is_valid()) {
+					return Err(error::ErrorBadRequest("Invalid buzzer settings!"));
+				}
+				if params
+					.language
+					.as_ref()
+					.is_some_and(|language| !game::is_valid_language_code(language))
+				{
+					return Err(error::ErrorBadRequest("Invalid language code!"));
+				}
+				//library question sets are loaded from the database, others from files;
+				// both are resolved before any setting is changed
+				let library_set = db
+					.get_library_revision(params.question_set.clone(), params.question_set_revision)
+					.await
+					.map_err(error::ErrorInternalServerError)?;
+				let file_set = if library_set.is_some() {
+					None
+				} else if params.question_set_revision.is_some() {
+					return Err(error::ErrorNotFound("Question set revision not found!"));
+				} else {
+					Some(game::load_question_set(&params.question_set)?)
+				};
+
+				if let Some(language) = &params.language {
+					lobby.set_language(language).await;
+				}
+				if let Some(special_jokers) = &params.special_jokers {
+					lobby.set_special_jokers(special_jokers.clone()).await;
+				}
+				if let Some(include_players) = params.audience_poll_includes_players {
+					lobby.set_audience_poll_includes_players(include_players).await;
+				}
+				if let Some(game_mode) = params.game_mode {
+					lobby.set_game_mode(game_mode).await;
+				}
+				if let Some(buzzer) = &params.buzzer {
+					lobby.set_buzzer_settings(buzzer.clone()).await;
+				}
+				if let Some(password) = &params.password {
+					lobby.set_password(password).await;
+				}
+				if let Some(listed) = params.listed {
+					lobby.set_listed(listed).await;
+				}
+				if let Some(max_players) = params.max_players {
+					lobby.set_max_players(max_players).await;
+				}
+				if let Some(approval_required) = params.approval_required {
+					lobby.set_approval_required(approval_required).await;
+				}
+				if let Some(lock_renames) = params.lock_renames {
+					lobby.set_lock_renames(lock_renames).await;
+				}
 				join!(
 					lobby.set_open(params.open),
 					lobby.update_preferences(
@@ -216,7 +604,21 @@ async fn update_lobby(
 						params.estimation_q_money
 					)
 				);
-				lobby.set_question_set(&params.question_set).await?;
+				if let Some(ruleset) = ruleset {
+					lobby.set_ruleset(ruleset).await;
+				}
+				if let Some(library_set) = library_set {
+					lobby
+						.set_library_question_set(
+							&params.question_set,
+							library_set.revision,
+							library_set.meta,
+							library_set.questions,
+						)
+						.await;
+				} else if let Some(question_file) = file_set {
+					lobby.set_question_set(&params.question_set, question_file).await;
+				}
 				Ok(HttpResponse::NoContent().finish())
 			} else {
 				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
@@ -233,23 +635,24 @@ async fn update_lobby(
 #[derive(Serialize, Deserialize)]
 struct UploadCustomQuestionsData {
 	lobby_id: String,
-	questions: Vec<game::Question>,
+	questions: game::QuestionSetFile,
 }
-#[post("/upload_custom_questions")]
 async fn upload_custom_questions(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Json<UploadCustomQuestionsData>,
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: UploadCustomQuestionsData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
+		if let Some(lobby) = db_lobby {
 			if lobby.get_admin_uuid().await == uuid {
+				if !params.questions.is_valid() {
+					return Err(error::ErrorBadRequest("Invalid round multiplier!"));
+				}
 				lobby.set_questions(params.questions.clone()).await?;
 				Ok(HttpResponse::NoContent().finish())
 			} else {
@@ -263,33 +666,43 @@ async fn upload_custom_questions(
 	}
 }
 
-// Kick a player from playing in the lobby
+// Download the lobby's current questions as file
 #[derive(Serialize, Deserialize)]
-struct KickPlayerData {
+struct ExportQuestionsData {
 	lobby_id: String,
-	name: String,
+	format: game::ExportFormat,
 }
-#[post("/kick_player")]
-async fn kick_player(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Json<KickPlayerData>,
+async fn export_questions(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: ExportQuestionsData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
+		if let Some(lobby) = db_lobby {
 			if lobby.get_admin_uuid().await == uuid {
-				let res = lobby.kick_player(&params.name).await;
-				if res {
-					Ok(HttpResponse::NoContent().finish())
-				} else {
-					Err(error::ErrorNotFound("Player name was not found"))
+				let question_file = lobby.get_question_file().await;
+				let language = lobby.get_language().await;
+				let content = game::export_questions(&question_file, params.format, &language)?;
+				let mut file_name = lobby.get_question_set().await;
+				if file_name.is_empty() {
+					file_name = String::from("questions");
 				}
+				Ok(HttpResponse::Ok()
+					.content_type(params.format.content_type())
+					.append_header((
+						"Content-Disposition",
+						format!(
+							"attachment; filename=\"{}.{}\"",
+							file_name,
+							params.format.extension()
+						),
+					))
+					.body(content))
 			} else {
 				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
 			}
@@ -301,40 +714,63 @@ async fn kick_player(
 	}
 }
 
-// Set a players attributes
+// Save the lobby's custom questions as new server side question set
 #[derive(Serialize, Deserialize)]
-struct SetPlayerAttributesData {
+struct SaveCustomQuestionsData {
 	lobby_id: String,
 	name: String,
-	money: i64,
-	jokers: usize,
 }
-#[post("/set_player_attributes")]
-async fn set_player_attributes(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Json<SetPlayerAttributesData>,
+async fn save_custom_questions(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: SaveCustomQuestionsData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
+
+	//saving is disabled unless the server allows it
+	let allow_saving =
+		env::var("ALLOW_SAVING_QUESTION_SETS").unwrap_or_default().parse().unwrap_or(false);
+	if !allow_saving {
+		return Err(error::ErrorForbidden("Saving question sets is disabled on this server!"));
+	}
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
+		if let Some(lobby) = db_lobby {
 			if lobby.get_admin_uuid().await == uuid {
-				if params.money < 1 {
-					return Err(error::ErrorBadRequest("Money must be at least 1!"));
+				if lobby.get_question_set().await != "custom" {
+					return Err(error::ErrorBadRequest("Lobby does not use custom questions!"));
 				}
-
-				let res =
-					lobby.set_player_attributes(&params.name, params.money, params.jokers).await;
-				if res {
-					Ok(HttpResponse::NoContent().finish())
-				} else {
-					Err(error::ErrorNotFound("Player name was not found"))
+				if !game::is_valid_question_set_name(&params.name) {
+					return Err(error::ErrorBadRequest(
+						"Invalid name: Only letters, digits, '-' and '_' are allowed!",
+					));
+				}
+				let question_file = lobby.get_question_file().await;
+				if question_file.is_empty() {
+					return Err(error::ErrorBadRequest("No custom questions were uploaded!"));
+				}
+				let library_set = db
+					.get_library_revision(params.name.clone(), None)
+					.await
+					.map_err(error::ErrorInternalServerError)?;
+				if library_set.is_some() {
+					return Err(error::ErrorConflict("Question set name is already in use!"));
+				}
+				match game::save_question_set(&params.name, &question_file) {
+					Ok(_) => {}
+					Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+						return Err(error::ErrorConflict("Question set name is already in use!"));
+					}
+					Err(err) => return Err(err.into()),
 				}
+				//make the new set available for future lobbies
+				db.set_question_sets(game::find_question_sets()?)
+					.await
+					.map_err(error::ErrorInternalServerError)?;
+				Ok(HttpResponse::Created().json(params.name.clone()))
 			} else {
 				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
 			}
@@ -346,28 +782,31 @@ async fn set_player_attributes(
 	}
 }
 
-// Activate (force) next lobby state
+// Kick a player from playing in the lobby
 #[derive(Serialize, Deserialize)]
-struct NextStateData {
+struct KickPlayerData {
 	lobby_id: String,
+	player_id: usize,
 }
-#[get("/next_state")]
-async fn next_state(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Query<NextStateData>,
+async fn kick_player(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: KickPlayerData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
+		if let Some(lobby) = db_lobby {
 			if lobby.get_admin_uuid().await == uuid {
-				lobby.next_state().await;
-				Ok(HttpResponse::NoContent().finish())
+				let res = lobby.kick_player(params.player_id).await;
+				if res {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotFound("Player ID was not found"))
+				}
 			} else {
 				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
 			}
@@ -379,40 +818,64 @@ async fn next_state(
 	}
 }
 
-// A player bets for a question
+// Let a player waiting in the waiting room join the lobby
 #[derive(Serialize, Deserialize)]
-struct BetMoneyData {
+struct JoinRequestData {
 	lobby_id: String,
-	money_bet: i64,
+	player_id: usize,
 }
-#[get("/bet_money")]
-async fn bet_money(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Query<BetMoneyData>,
+async fn accept_join_request(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: JoinRequestData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
-			let player_money = lobby.get_player_money(&uuid).await;
-			if player_money.is_none() {
-				return Err(error::ErrorNotFound("Player(you) not found"));
-			}
-			if params.money_bet < 1 || params.money_bet > player_money.unwrap() {
-				return Err(error::ErrorBadRequest(
-					"Money_bet is invalid (< 1 or > player money)!",
-				));
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				let res = lobby.accept_join_request(params.player_id).await;
+				match res {
+					Ok(name) => Ok(HttpResponse::Ok().json(name)),
+					Err(game::JoinError::Full) => Err(error::ErrorConflict("Lobby is full!")),
+					Err(err) => Err(error::ErrorNotFound(err)),
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
 			}
-			let res = lobby.bet(&uuid, params.money_bet).await;
-			if res {
-				Ok(HttpResponse::NoContent().finish())
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Turn away a player waiting in the waiting room
+async fn reject_join_request(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: JoinRequestData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				let res = lobby.reject_join_request(params.player_id).await;
+				if res {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotFound("Join request not found"))
+				}
 			} else {
-				Err(error::ErrorNotAcceptable("Game lobby is in wrong state!"))
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
 			}
 		} else {
 			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
@@ -422,39 +885,35 @@ async fn bet_money(
 	}
 }
 
-// A player selects a player to attack
+// Ban a player from the lobby, optionally for a number of seconds
 #[derive(Serialize, Deserialize)]
-struct AttackPlayerData {
+struct BanPlayerData {
 	lobby_id: String,
-	vs_player: String,
+	player_id: usize,
+	duration: Option<u64>,
 }
-#[get("/attack_player")]
-async fn attack_player(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Query<AttackPlayerData>,
+async fn ban_player(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: BanPlayerData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
-			let (player_name, valid_vs_player) =
-				join!(lobby.get_player_name(&uuid), lobby.is_valid_vs_player(&params.vs_player));
-			if player_name.is_none() {
-				return Err(error::ErrorNotFound("Player(you) not found"));
-			}
-			if !valid_vs_player || params.vs_player == player_name.unwrap() {
-				return Err(error::ErrorBadRequest("Vs_player ist invalid!"));
-			}
-			let res = lobby.attack(&uuid, &params.vs_player).await;
-			if res {
-				Ok(HttpResponse::NoContent().finish())
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				let duration = params.duration.map(Duration::from_secs);
+				let res = lobby.ban_player(params.player_id, duration).await;
+				if res {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotFound("Player ID was not found"))
+				}
 			} else {
-				Err(error::ErrorNotAcceptable("Game lobby is in wrong state!"))
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
 			}
 		} else {
 			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
@@ -464,37 +923,33 @@ async fn attack_player(
 	}
 }
 
-// A player answers a question
+// Lift the ban of a player
 #[derive(Serialize, Deserialize)]
-struct AnswerQuestionData {
+struct UnbanPlayerData {
 	lobby_id: String,
-	answer: usize,
+	player_id: usize,
 }
-#[get("/answer_question")]
-async fn answer_question(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Query<AnswerQuestionData>,
+async fn unban_player(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: UnbanPlayerData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
-			if !lobby.is_joined(&uuid).await {
-				return Err(error::ErrorNotFound("Player(you) not found"));
-			}
-			if params.answer < 1 {
-				return Err(error::ErrorBadRequest("Answer is invalid (< 1)!"));
-			}
-			let res = lobby.answer(&uuid, params.answer).await;
-			if res {
-				Ok(HttpResponse::NoContent().finish())
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				let res = lobby.unban_player(params.player_id).await;
+				if res {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotFound("No ban found for this player ID"))
+				}
 			} else {
-				Err(error::ErrorNotAcceptable("Game lobby is in wrong state!"))
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
 			}
 		} else {
 			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
@@ -504,37 +959,680 @@ async fn answer_question(
 	}
 }
 
-// A player retrieves wrong answers per joker
+// List the bans of the lobby
 #[derive(Serialize, Deserialize)]
-struct GetJokerData {
+struct GetBansData {
 	lobby_id: String,
 }
-#[get("/get_joker")]
-async fn get_joker(
-	db: web::Data<DataHandler>,
-	session: Session,
-	request: HttpRequest,
-	params: web::Query<GetJokerData>,
+async fn get_bans(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: GetBansData,
 ) -> HttpResult<HttpResponse> {
-	ensure_cookie_consent(&request)?;
+	ensure_cookie_consent(request)?;
 
 	if let Some(uuid) = session.get::<String>("uuid")? {
 		let db_lobby =
 			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
-		if db_lobby.is_some() {
-			let lobby = db_lobby.unwrap();
-			let player_jokers = lobby.get_player_jokers(&uuid).await;
-			if player_jokers.is_none() {
-				return Err(error::ErrorNotFound("Player(you) not found"));
-			}
-			if player_jokers.unwrap() < 1 {
-				return Err(error::ErrorBadRequest("You have no jokers!"));
-			}
-			let res = lobby.get_joker(&uuid).await;
-			if res.is_some() {
-				Ok(HttpResponse::Ok().json(res.unwrap()))
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				Ok(HttpResponse::Ok().json(lobby.get_bans().await))
 			} else {
-				Err(error::ErrorNotAcceptable("Game lobby is in wrong state!"))
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Set a players attributes
+#[derive(Serialize, Deserialize)]
+struct SetPlayerAttributesData {
+	lobby_id: String,
+	player_id: usize,
+	money: i64,
+	jokers: usize,
+	special_jokers: Option<BTreeMap<game::JokerType, usize>>,
+}
+async fn set_player_attributes(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: SetPlayerAttributesData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				if params.money < 1 {
+					return Err(error::ErrorBadRequest("Money must be at least 1!"));
+				}
+
+				let res = lobby
+					.set_player_attributes(
+						params.player_id,
+						params.money,
+						params.jokers,
+						params.special_jokers.clone(),
+					)
+					.await;
+				if res {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotFound("Player ID was not found"))
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Activate (force) next lobby state
+#[derive(Serialize, Deserialize)]
+struct NextStateData {
+	lobby_id: String,
+}
+async fn next_state(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: NextStateData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				lobby.next_state().await;
+				Ok(HttpResponse::NoContent().finish())
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Roll the game back to before the last scoring step
+#[derive(Serialize, Deserialize)]
+struct RollBackData {
+	lobby_id: String,
+}
+async fn roll_back(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: RollBackData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				if lobby.roll_back().await {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotAcceptable("Nothing to roll back!"))
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Judge the answer of a player for a question the host judges
+#[derive(Serialize, Deserialize)]
+struct JudgeAnswerData {
+	lobby_id: String,
+	player_id: usize,
+	correct: bool,
+}
+async fn judge_answer(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: JudgeAnswerData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				match lobby.judge(params.player_id, params.correct).await {
+					Ok(()) => Ok(HttpResponse::NoContent().finish()),
+					Err(err @ game::JudgeError::WrongState) => Err(error::ErrorNotAcceptable(err)),
+					Err(err @ game::JudgeError::PlayerNotFound) => Err(error::ErrorNotFound(err)),
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Pause the running game
+#[derive(Serialize, Deserialize)]
+struct PauseGameData {
+	lobby_id: String,
+}
+async fn pause_game(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: PauseGameData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				if lobby.pause().await {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotAcceptable("Game is not running or already paused!"))
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Resume the paused game
+#[derive(Serialize, Deserialize)]
+struct ResumeGameData {
+	lobby_id: String,
+}
+async fn resume_game(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: ResumeGameData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				if lobby.resume().await {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotAcceptable("Game is not paused!"))
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Skip the current question without scoring it
+#[derive(Serialize, Deserialize)]
+struct SkipQuestionData {
+	lobby_id: String,
+}
+async fn skip_question(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: SkipQuestionData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				if lobby.navigate(game::Navigation::Skip).await {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotAcceptable("Game is not running!"))
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Go back and play the previous question again
+#[derive(Serialize, Deserialize)]
+struct RepeatQuestionData {
+	lobby_id: String,
+}
+async fn repeat_question(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: RepeatQuestionData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				if lobby.navigate(game::Navigation::Repeat).await {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotAcceptable(
+						"Game is not running or there is no previous question!",
+					))
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// Continue the game with the given question
+#[derive(Serialize, Deserialize)]
+struct JumpToQuestionData {
+	lobby_id: String,
+	question: usize,
+}
+async fn jump_to_question(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: JumpToQuestionData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if lobby.get_admin_uuid().await == uuid {
+				if lobby.navigate(game::Navigation::Jump(params.question)).await {
+					Ok(HttpResponse::NoContent().finish())
+				} else {
+					Err(error::ErrorNotAcceptable(
+						"Game is not running or question does not exist!",
+					))
+				}
+			} else {
+				Err(error::ErrorUnauthorized("You are not the lobby admin!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// A player bets for a question
+#[derive(Serialize, Deserialize)]
+struct BetMoneyData {
+	lobby_id: String,
+	money_bet: i64,
+}
+async fn bet_money(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: BetMoneyData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			let player_money = lobby.get_player_money(&uuid).await;
+			let player_money = match player_money {
+				Some(player_money) => player_money,
+				None => return Err(error::ErrorNotFound("Player(you) not found")),
+			};
+			if !lobby.get_ruleset().await.is_valid_bet(params.money_bet, player_money) {
+				return Err(error::ErrorBadRequest(
+					"Money_bet is invalid (outside of betting limits or > player money)!",
+				));
+			}
+			if lobby.is_eliminated(&uuid).await {
+				return Err(error::ErrorForbidden("You are eliminated!"));
+			}
+			if lobby.is_paused() {
+				return Err(error::ErrorLocked("Game is paused!"));
+			}
+			let res = lobby.bet(&uuid, params.money_bet).await;
+			if res {
+				Ok(HttpResponse::NoContent().finish())
+			} else {
+				Err(error::ErrorNotAcceptable("Game lobby is in wrong state!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// A player secretly wagers money on the final question
+#[derive(Serialize, Deserialize)]
+struct WagerMoneyData {
+	lobby_id: String,
+	money_wager: i64,
+}
+async fn wager_money(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: WagerMoneyData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			let player_money = lobby.get_player_money(&uuid).await;
+			let player_money = match player_money {
+				Some(player_money) => player_money,
+				None => return Err(error::ErrorNotFound("Player(you) not found")),
+			};
+			if params.money_wager < 0 || params.money_wager > player_money.max(0) {
+				return Err(error::ErrorBadRequest(
+					"Money_wager is invalid (< 0 or > player money)!",
+				));
+			}
+			if lobby.is_eliminated(&uuid).await {
+				return Err(error::ErrorForbidden("You are eliminated!"));
+			}
+			if lobby.is_paused() {
+				return Err(error::ErrorLocked("Game is paused!"));
+			}
+			let res = lobby.wager(&uuid, params.money_wager).await;
+			if res {
+				Ok(HttpResponse::NoContent().finish())
+			} else {
+				Err(error::ErrorNotAcceptable("Game lobby is in wrong state!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// A player selects a player to attack
+#[derive(Serialize, Deserialize)]
+struct AttackPlayerData {
+	lobby_id: String,
+	vs_player: usize,
+}
+async fn attack_player(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: AttackPlayerData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			let (player_id, valid_vs_player) =
+				join!(lobby.get_player_id(&uuid), lobby.is_valid_vs_player(params.vs_player));
+			if player_id.is_none() {
+				return Err(error::ErrorNotFound("Player(you) not found"));
+			}
+			if !valid_vs_player || params.vs_player == player_id.unwrap() {
+				return Err(error::ErrorBadRequest("Vs_player ist invalid!"));
+			}
+			if lobby.is_eliminated(&uuid).await {
+				return Err(error::ErrorForbidden("You are eliminated!"));
+			}
+			if lobby.is_paused() {
+				return Err(error::ErrorLocked("Game is paused!"));
+			}
+			let res = lobby.attack(&uuid, params.vs_player).await;
+			if res {
+				Ok(HttpResponse::NoContent().finish())
+			} else {
+				Err(error::ErrorNotAcceptable("Game lobby is in wrong state!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// A player answers a question
+#[derive(Serialize, Deserialize)]
+struct AnswerQuestionData {
+	lobby_id: String,
+	answer: usize,
+}
+async fn answer_question(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: AnswerQuestionData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if !lobby.is_joined(&uuid).await {
+				return Err(error::ErrorNotFound("Player(you) not found"));
+			}
+			if params.answer < 1 {
+				return Err(error::ErrorBadRequest("Answer is invalid (< 1)!"));
+			}
+			if lobby.is_eliminated(&uuid).await {
+				return Err(error::ErrorForbidden("You are eliminated!"));
+			}
+			if lobby.is_paused() {
+				return Err(error::ErrorLocked("Game is paused!"));
+			}
+			let res = lobby.answer(&uuid, params.answer).await;
+			if res {
+				Ok(HttpResponse::NoContent().finish())
+			} else {
+				Err(error::ErrorNotAcceptable("Game lobby is in wrong state!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// A player retrieves wrong answers per joker
+#[derive(Serialize, Deserialize)]
+struct GetJokerData {
+	lobby_id: String,
+	#[serde(default = "default_joker")]
+	joker: game::JokerType,
+}
+fn default_joker() -> game::JokerType {
+	game::JokerType::FiftyFifty
+}
+async fn get_joker(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: GetJokerData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			match lobby.get_joker(&uuid, params.joker).await {
+				Ok(game::JokerResult::Activated) => Ok(HttpResponse::NoContent().finish()),
+				Ok(res) => Ok(HttpResponse::Ok().json(res)),
+				Err(err @ game::JokerError::WrongState) => Err(error::ErrorNotAcceptable(err)),
+				Err(err @ game::JokerError::PlayerNotFound) => Err(error::ErrorNotFound(err)),
+				Err(err @ game::JokerError::NoHint) => Err(error::ErrorNotFound(err)),
+				Err(err @ game::JokerError::Eliminated) => Err(error::ErrorForbidden(err)),
+				Err(err @ game::JokerError::Paused) => Err(error::ErrorLocked(err)),
+				Err(err) => Err(error::ErrorBadRequest(err)),
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// A spectator votes in a running audience poll
+#[derive(Serialize, Deserialize)]
+struct VoteAudiencePollData {
+	lobby_id: String,
+	answer: usize,
+}
+async fn vote_audience_poll(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: VoteAudiencePollData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			if !(1..=4).contains(&params.answer) {
+				return Err(error::ErrorBadRequest("Answer is invalid (not 1-4)!"));
+			}
+			let res = lobby.vote_audience_poll(&uuid, params.answer).await;
+			if res {
+				Ok(HttpResponse::NoContent().finish())
+			} else {
+				Err(error::ErrorNotAcceptable("No audience poll open for you to vote in!"))
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// The choosing player picks the next question from the board
+#[derive(Serialize, Deserialize)]
+struct SelectQuestionData {
+	lobby_id: String,
+	question: usize,
+}
+async fn select_question(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: SelectQuestionData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			match lobby.select_question(&uuid, params.question).await {
+				Ok(()) => Ok(HttpResponse::NoContent().finish()),
+				Err(err @ game::BoardError::WrongState) => Err(error::ErrorNotAcceptable(err)),
+				Err(err @ game::BoardError::NotChooser) => Err(error::ErrorForbidden(err)),
+				Err(err @ game::BoardError::InvalidQuestion) => Err(error::ErrorBadRequest(err)),
+				Err(err @ game::BoardError::Paused) => Err(error::ErrorLocked(err)),
+			}
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+// A player buzzes to get the answer right for a buzzer question
+#[derive(Serialize, Deserialize)]
+struct BuzzData {
+	lobby_id: String,
+}
+async fn buzz(
+	db: &DataHandler,
+	session: &Session,
+	request: &HttpRequest,
+	params: BuzzData,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(params.lobby_id.clone()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			match lobby.buzz(&uuid).await {
+				Ok(()) => Ok(HttpResponse::NoContent().finish()),
+				Err(err @ game::BuzzError::WrongState) => Err(error::ErrorNotAcceptable(err)),
+				Err(err @ game::BuzzError::PlayerNotFound) => Err(error::ErrorNotFound(err)),
+				Err(err @ game::BuzzError::Eliminated) => Err(error::ErrorForbidden(err)),
+				Err(err @ game::BuzzError::LockedOut) => Err(error::ErrorConflict(err)),
+				Err(err @ game::BuzzError::Paused) => Err(error::ErrorLocked(err)),
 			}
 		} else {
 			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
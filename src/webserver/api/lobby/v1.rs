@@ -0,0 +1,435 @@
+//original routes of the lobby API, kept for existing clients
+
+use actix_session::Session;
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Result as HttpResult};
+
+use super::{
+	AnswerQuestionData, AttackPlayerData, BanPlayerData, BetMoneyData, BuzzData,
+	CreateReconnectTokenData, ExportQuestionsData, GetBansData, GetEventsData, GetJoinCodeData,
+	GetJokerData, GetPlayerDataData, JoinLobbyData, JoinRequestData, JudgeAnswerData,
+	JumpToQuestionData, KickPlayerData, LeaveLobbyData, ListLobbiesData, NextStateData,
+	PauseGameData, RedeemReconnectTokenData, RegenerateJoinCodeData, RepeatQuestionData,
+	ResumeGameData, RollBackData, SaveCustomQuestionsData, SelectQuestionData,
+	SetPlayerAttributesData, SkipQuestionData, UnbanPlayerData, UpdateLobbyData,
+	UploadCustomQuestionsData, VoteAudiencePollData, WagerMoneyData,
+};
+use crate::datahandler::DataHandler;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+	cfg.service(create_lobby)
+		.service(list_lobbies)
+		.service(join_lobby)
+		.service(leave_lobby)
+		.service(create_reconnect_token)
+		.service(redeem_reconnect_token)
+		.service(get_join_code)
+		.service(regenerate_join_code)
+		.service(get_events)
+		.service(get_private_events)
+		.service(get_player_data)
+		.service(update_lobby)
+		.service(upload_custom_questions)
+		.service(export_questions)
+		.service(save_custom_questions)
+		.service(kick_player)
+		.service(accept_join_request)
+		.service(reject_join_request)
+		.service(ban_player)
+		.service(unban_player)
+		.service(get_bans)
+		.service(set_player_attributes)
+		.service(next_state)
+		.service(roll_back)
+		.service(judge_answer)
+		.service(pause_game)
+		.service(resume_game)
+		.service(skip_question)
+		.service(repeat_question)
+		.service(jump_to_question)
+		.service(bet_money)
+		.service(wager_money)
+		.service(attack_player)
+		.service(answer_question)
+		.service(get_joker)
+		.service(vote_audience_poll)
+		.service(select_question)
+		.service(buzz);
+}
+
+#[get("/create_lobby")]
+async fn create_lobby(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+) -> HttpResult<HttpResponse> {
+	super::create_lobby(&db, &session, &request).await
+}
+
+#[get("/list_lobbies")]
+async fn list_lobbies(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Query<ListLobbiesData>,
+) -> HttpResult<HttpResponse> {
+	super::list_lobbies(&db, &request, params.into_inner()).await
+}
+
+#[get("/join_lobby")]
+async fn join_lobby(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<JoinLobbyData>,
+) -> HttpResult<HttpResponse> {
+	super::join_lobby(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/leave_lobby")]
+async fn leave_lobby(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<LeaveLobbyData>,
+) -> HttpResult<HttpResponse> {
+	super::leave_lobby(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/create_reconnect_token")]
+async fn create_reconnect_token(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<CreateReconnectTokenData>,
+) -> HttpResult<HttpResponse> {
+	super::create_reconnect_token(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/redeem_reconnect_token")]
+async fn redeem_reconnect_token(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<RedeemReconnectTokenData>,
+) -> HttpResult<HttpResponse> {
+	super::redeem_reconnect_token(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/get_join_code")]
+async fn get_join_code(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<GetJoinCodeData>,
+) -> HttpResult<HttpResponse> {
+	super::get_join_code(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/regenerate_join_code")]
+async fn regenerate_join_code(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<RegenerateJoinCodeData>,
+) -> HttpResult<HttpResponse> {
+	super::regenerate_join_code(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/get_events")]
+async fn get_events(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<GetEventsData>,
+) -> HttpResult<HttpResponse> {
+	super::get_events(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/get_private_events")]
+async fn get_private_events(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<GetEventsData>,
+) -> HttpResult<HttpResponse> {
+	super::get_private_events(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/get_player_data")]
+async fn get_player_data(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<GetPlayerDataData>,
+) -> HttpResult<HttpResponse> {
+	super::get_player_data(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/update_lobby")]
+async fn update_lobby(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<UpdateLobbyData>,
+) -> HttpResult<HttpResponse> {
+	super::update_lobby(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/upload_custom_questions")]
+async fn upload_custom_questions(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<UploadCustomQuestionsData>,
+) -> HttpResult<HttpResponse> {
+	super::upload_custom_questions(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/export_questions")]
+async fn export_questions(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<ExportQuestionsData>,
+) -> HttpResult<HttpResponse> {
+	super::export_questions(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/save_custom_questions")]
+async fn save_custom_questions(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<SaveCustomQuestionsData>,
+) -> HttpResult<HttpResponse> {
+	super::save_custom_questions(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/kick_player")]
+async fn kick_player(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<KickPlayerData>,
+) -> HttpResult<HttpResponse> {
+	super::kick_player(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/accept_join_request")]
+async fn accept_join_request(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<JoinRequestData>,
+) -> HttpResult<HttpResponse> {
+	super::accept_join_request(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/reject_join_request")]
+async fn reject_join_request(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<JoinRequestData>,
+) -> HttpResult<HttpResponse> {
+	super::reject_join_request(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/ban_player")]
+async fn ban_player(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<BanPlayerData>,
+) -> HttpResult<HttpResponse> {
+	super::ban_player(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/unban_player")]
+async fn unban_player(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<UnbanPlayerData>,
+) -> HttpResult<HttpResponse> {
+	super::unban_player(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/get_bans")]
+async fn get_bans(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<GetBansData>,
+) -> HttpResult<HttpResponse> {
+	super::get_bans(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/set_player_attributes")]
+async fn set_player_attributes(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<SetPlayerAttributesData>,
+) -> HttpResult<HttpResponse> {
+	super::set_player_attributes(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/next_state")]
+async fn next_state(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<NextStateData>,
+) -> HttpResult<HttpResponse> {
+	super::next_state(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/roll_back")]
+async fn roll_back(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<RollBackData>,
+) -> HttpResult<HttpResponse> {
+	super::roll_back(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/judge_answer")]
+async fn judge_answer(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<JudgeAnswerData>,
+) -> HttpResult<HttpResponse> {
+	super::judge_answer(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/pause_game")]
+async fn pause_game(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<PauseGameData>,
+) -> HttpResult<HttpResponse> {
+	super::pause_game(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/resume_game")]
+async fn resume_game(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<ResumeGameData>,
+) -> HttpResult<HttpResponse> {
+	super::resume_game(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/skip_question")]
+async fn skip_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<SkipQuestionData>,
+) -> HttpResult<HttpResponse> {
+	super::skip_question(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/repeat_question")]
+async fn repeat_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<RepeatQuestionData>,
+) -> HttpResult<HttpResponse> {
+	super::repeat_question(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/jump_to_question")]
+async fn jump_to_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<JumpToQuestionData>,
+) -> HttpResult<HttpResponse> {
+	super::jump_to_question(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/bet_money")]
+async fn bet_money(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<BetMoneyData>,
+) -> HttpResult<HttpResponse> {
+	super::bet_money(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/wager_money")]
+async fn wager_money(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<WagerMoneyData>,
+) -> HttpResult<HttpResponse> {
+	super::wager_money(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/attack_player")]
+async fn attack_player(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<AttackPlayerData>,
+) -> HttpResult<HttpResponse> {
+	super::attack_player(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/answer_question")]
+async fn answer_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<AnswerQuestionData>,
+) -> HttpResult<HttpResponse> {
+	super::answer_question(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/get_joker")]
+async fn get_joker(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<GetJokerData>,
+) -> HttpResult<HttpResponse> {
+	super::get_joker(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/vote_audience_poll")]
+async fn vote_audience_poll(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<VoteAudiencePollData>,
+) -> HttpResult<HttpResponse> {
+	super::vote_audience_poll(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/select_question")]
+async fn select_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<SelectQuestionData>,
+) -> HttpResult<HttpResponse> {
+	super::select_question(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/buzz")]
+async fn buzz(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<BuzzData>,
+) -> HttpResult<HttpResponse> {
+	super::buzz(&db, &session, &request, params.into_inner()).await
+}
@@ -0,0 +1,569 @@
+//resource oriented routes of the lobby API, taking JSON bodies; lobbies are
+// addressed by their UUID (or join code when joining), players by public ID
+
+use std::collections::BTreeMap;
+
+use actix_session::Session;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Result as HttpResult};
+use serde::{Deserialize, Serialize};
+
+use super::{
+	default_joker, AnswerQuestionData, AttackPlayerData, BanPlayerData, BetMoneyData, BuzzData,
+	CreateReconnectTokenData, ExportQuestionsData, GetBansData, GetEventsData, GetJoinCodeData,
+	GetJokerData, GetPlayerDataData, JoinLobbyData, JoinRequestData, JudgeAnswerData,
+	JumpToQuestionData, KickPlayerData, LeaveLobbyData, ListLobbiesData, LobbySettingsData,
+	NextStateData, PauseGameData, RedeemReconnectTokenData, RegenerateJoinCodeData,
+	RepeatQuestionData, ResumeGameData, RollBackData, SaveCustomQuestionsData, SelectQuestionData,
+	SetPlayerAttributesData, SkipQuestionData, UnbanPlayerData, UpdateLobbyData,
+	UploadCustomQuestionsData, VoteAudiencePollData, WagerMoneyData,
+};
+use crate::{datahandler::DataHandler, game};
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+	cfg.service(create_lobby)
+		.service(list_lobbies)
+		.service(update_lobby)
+		.service(join_lobby)
+		.service(leave_lobby)
+		.service(get_player_data)
+		.service(kick_player)
+		.service(set_player_attributes)
+		.service(judge_answer)
+		.service(get_join_code)
+		.service(regenerate_join_code)
+		.service(accept_join_request)
+		.service(reject_join_request)
+		.service(ban_player)
+		.service(unban_player)
+		.service(get_bans)
+		.service(create_reconnect_token)
+		.service(redeem_reconnect_token)
+		.service(get_events)
+		.service(get_private_events)
+		.service(upload_custom_questions)
+		.service(export_questions)
+		.service(save_custom_questions)
+		.service(next_state)
+		.service(roll_back)
+		.service(pause_game)
+		.service(resume_game)
+		.service(skip_question)
+		.service(repeat_question)
+		.service(jump_to_question)
+		.service(bet_money)
+		.service(wager_money)
+		.service(attack_player)
+		.service(answer_question)
+		.service(get_joker)
+		.service(vote_audience_poll)
+		.service(select_question)
+		.service(buzz);
+}
+
+#[post("/lobbies")]
+async fn create_lobby(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+) -> HttpResult<HttpResponse> {
+	super::create_lobby(&db, &session, &request).await
+}
+
+#[get("/lobbies")]
+async fn list_lobbies(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+	params: web::Query<ListLobbiesData>,
+) -> HttpResult<HttpResponse> {
+	super::list_lobbies(&db, &request, params.into_inner()).await
+}
+
+#[put("/lobbies/{lobby_id}/settings")]
+async fn update_lobby(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<LobbySettingsData>,
+) -> HttpResult<HttpResponse> {
+	let params = UpdateLobbyData { lobby_id: path.into_inner(), settings: params.into_inner() };
+	super::update_lobby(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct JoinBody {
+	language: Option<String>,
+}
+#[post("/lobbies/{lobby_id}/players")]
+async fn join_lobby(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<JoinBody>,
+) -> HttpResult<HttpResponse> {
+	let params = JoinLobbyData { uuid: path.into_inner(), language: params.into_inner().language };
+	super::join_lobby(&db, &session, &request, params).await
+}
+
+//registered before the routes taking a player ID
+#[delete("/lobbies/{lobby_id}/players/me")]
+async fn leave_lobby(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = LeaveLobbyData { uuid: path.into_inner() };
+	super::leave_lobby(&db, &session, &request, params).await
+}
+
+#[get("/lobbies/{lobby_id}/players")]
+async fn get_player_data(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = GetPlayerDataData { lobby_id: path.into_inner() };
+	super::get_player_data(&db, &session, &request, params).await
+}
+
+#[delete("/lobbies/{lobby_id}/players/{player_id}")]
+async fn kick_player(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<(String, usize)>,
+) -> HttpResult<HttpResponse> {
+	let (lobby_id, player_id) = path.into_inner();
+	let params = KickPlayerData { lobby_id, player_id };
+	super::kick_player(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlayerAttributesBody {
+	money: i64,
+	jokers: usize,
+	special_jokers: Option<BTreeMap<game::JokerType, usize>>,
+}
+#[put("/lobbies/{lobby_id}/players/{player_id}/attributes")]
+async fn set_player_attributes(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<(String, usize)>,
+	params: web::Json<PlayerAttributesBody>,
+) -> HttpResult<HttpResponse> {
+	let (lobby_id, player_id) = path.into_inner();
+	let params = params.into_inner();
+	let params = SetPlayerAttributesData {
+		lobby_id,
+		player_id,
+		money: params.money,
+		jokers: params.jokers,
+		special_jokers: params.special_jokers,
+	};
+	super::set_player_attributes(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct JudgementBody {
+	correct: bool,
+}
+#[put("/lobbies/{lobby_id}/players/{player_id}/judgement")]
+async fn judge_answer(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<(String, usize)>,
+	params: web::Json<JudgementBody>,
+) -> HttpResult<HttpResponse> {
+	let (lobby_id, player_id) = path.into_inner();
+	let params = JudgeAnswerData { lobby_id, player_id, correct: params.correct };
+	super::judge_answer(&db, &session, &request, params).await
+}
+
+#[get("/lobbies/{lobby_id}/join_code")]
+async fn get_join_code(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = GetJoinCodeData { lobby_id: path.into_inner() };
+	super::get_join_code(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct JoinCodeBody {
+	valid_for: Option<u64>,
+}
+#[post("/lobbies/{lobby_id}/join_code")]
+async fn regenerate_join_code(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<JoinCodeBody>,
+) -> HttpResult<HttpResponse> {
+	let params =
+		RegenerateJoinCodeData { lobby_id: path.into_inner(), valid_for: params.valid_for };
+	super::regenerate_join_code(&db, &session, &request, params).await
+}
+
+#[post("/lobbies/{lobby_id}/join_requests/{player_id}/accept")]
+async fn accept_join_request(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<(String, usize)>,
+) -> HttpResult<HttpResponse> {
+	let (lobby_id, player_id) = path.into_inner();
+	let params = JoinRequestData { lobby_id, player_id };
+	super::accept_join_request(&db, &session, &request, params).await
+}
+
+#[delete("/lobbies/{lobby_id}/join_requests/{player_id}")]
+async fn reject_join_request(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<(String, usize)>,
+) -> HttpResult<HttpResponse> {
+	let (lobby_id, player_id) = path.into_inner();
+	let params = JoinRequestData { lobby_id, player_id };
+	super::reject_join_request(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct BanBody {
+	player_id: usize,
+	duration: Option<u64>,
+}
+#[post("/lobbies/{lobby_id}/bans")]
+async fn ban_player(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<BanBody>,
+) -> HttpResult<HttpResponse> {
+	let params = BanPlayerData {
+		lobby_id: path.into_inner(),
+		player_id: params.player_id,
+		duration: params.duration,
+	};
+	super::ban_player(&db, &session, &request, params).await
+}
+
+#[delete("/lobbies/{lobby_id}/bans/{player_id}")]
+async fn unban_player(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<(String, usize)>,
+) -> HttpResult<HttpResponse> {
+	let (lobby_id, player_id) = path.into_inner();
+	let params = UnbanPlayerData { lobby_id, player_id };
+	super::unban_player(&db, &session, &request, params).await
+}
+
+#[get("/lobbies/{lobby_id}/bans")]
+async fn get_bans(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = GetBansData { lobby_id: path.into_inner() };
+	super::get_bans(&db, &session, &request, params).await
+}
+
+#[post("/lobbies/{lobby_id}/reconnect_tokens")]
+async fn create_reconnect_token(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = CreateReconnectTokenData { lobby_id: path.into_inner() };
+	super::create_reconnect_token(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReconnectionBody {
+	token: String,
+}
+#[post("/lobbies/{lobby_id}/reconnections")]
+async fn redeem_reconnect_token(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<ReconnectionBody>,
+) -> HttpResult<HttpResponse> {
+	let params =
+		RedeemReconnectTokenData { lobby_id: path.into_inner(), token: params.into_inner().token };
+	super::redeem_reconnect_token(&db, &session, &request, params).await
+}
+
+#[get("/lobbies/{lobby_id}/events")]
+async fn get_events(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = GetEventsData { lobby_id: path.into_inner() };
+	super::get_events(&db, &session, &request, params).await
+}
+
+#[get("/lobbies/{lobby_id}/private_events")]
+async fn get_private_events(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = GetEventsData { lobby_id: path.into_inner() };
+	super::get_private_events(&db, &session, &request, params).await
+}
+
+#[put("/lobbies/{lobby_id}/questions")]
+async fn upload_custom_questions(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<game::QuestionSetFile>,
+) -> HttpResult<HttpResponse> {
+	let params =
+		UploadCustomQuestionsData { lobby_id: path.into_inner(), questions: params.into_inner() };
+	super::upload_custom_questions(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct ExportQuery {
+	format: game::ExportFormat,
+}
+#[get("/lobbies/{lobby_id}/questions")]
+async fn export_questions(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	query: web::Query<ExportQuery>,
+) -> HttpResult<HttpResponse> {
+	let params = ExportQuestionsData { lobby_id: path.into_inner(), format: query.format };
+	super::export_questions(&db, &session, &request, params).await
+}
+
+//the lobby's custom questions become a new server side question set
+#[post("/question_sets")]
+async fn save_custom_questions(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<SaveCustomQuestionsData>,
+) -> HttpResult<HttpResponse> {
+	super::save_custom_questions(&db, &session, &request, params.into_inner()).await
+}
+
+#[post("/lobbies/{lobby_id}/next_state")]
+async fn next_state(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = NextStateData { lobby_id: path.into_inner() };
+	super::next_state(&db, &session, &request, params).await
+}
+
+#[post("/lobbies/{lobby_id}/roll_back")]
+async fn roll_back(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = RollBackData { lobby_id: path.into_inner() };
+	super::roll_back(&db, &session, &request, params).await
+}
+
+#[put("/lobbies/{lobby_id}/pause")]
+async fn pause_game(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = PauseGameData { lobby_id: path.into_inner() };
+	super::pause_game(&db, &session, &request, params).await
+}
+
+#[delete("/lobbies/{lobby_id}/pause")]
+async fn resume_game(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = ResumeGameData { lobby_id: path.into_inner() };
+	super::resume_game(&db, &session, &request, params).await
+}
+
+#[post("/lobbies/{lobby_id}/skip_question")]
+async fn skip_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = SkipQuestionData { lobby_id: path.into_inner() };
+	super::skip_question(&db, &session, &request, params).await
+}
+
+#[post("/lobbies/{lobby_id}/repeat_question")]
+async fn repeat_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = RepeatQuestionData { lobby_id: path.into_inner() };
+	super::repeat_question(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct QuestionBody {
+	question: usize,
+}
+#[put("/lobbies/{lobby_id}/current_question")]
+async fn jump_to_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<QuestionBody>,
+) -> HttpResult<HttpResponse> {
+	let params = JumpToQuestionData { lobby_id: path.into_inner(), question: params.question };
+	super::jump_to_question(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct BetBody {
+	money_bet: i64,
+}
+#[post("/lobbies/{lobby_id}/bets")]
+async fn bet_money(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<BetBody>,
+) -> HttpResult<HttpResponse> {
+	let params = BetMoneyData { lobby_id: path.into_inner(), money_bet: params.money_bet };
+	super::bet_money(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct WagerBody {
+	money_wager: i64,
+}
+#[post("/lobbies/{lobby_id}/wagers")]
+async fn wager_money(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<WagerBody>,
+) -> HttpResult<HttpResponse> {
+	let params = WagerMoneyData { lobby_id: path.into_inner(), money_wager: params.money_wager };
+	super::wager_money(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct AttackBody {
+	vs_player: usize,
+}
+#[post("/lobbies/{lobby_id}/attacks")]
+async fn attack_player(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<AttackBody>,
+) -> HttpResult<HttpResponse> {
+	let params = AttackPlayerData { lobby_id: path.into_inner(), vs_player: params.vs_player };
+	super::attack_player(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct AnswerBody {
+	answer: usize,
+}
+#[post("/lobbies/{lobby_id}/answers")]
+async fn answer_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<AnswerBody>,
+) -> HttpResult<HttpResponse> {
+	let params = AnswerQuestionData { lobby_id: path.into_inner(), answer: params.answer };
+	super::answer_question(&db, &session, &request, params).await
+}
+
+#[derive(Serialize, Deserialize)]
+struct JokerBody {
+	#[serde(default = "default_joker")]
+	joker: game::JokerType,
+}
+#[post("/lobbies/{lobby_id}/jokers")]
+async fn get_joker(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<JokerBody>,
+) -> HttpResult<HttpResponse> {
+	let params = GetJokerData { lobby_id: path.into_inner(), joker: params.joker };
+	super::get_joker(&db, &session, &request, params).await
+}
+
+#[post("/lobbies/{lobby_id}/poll_votes")]
+async fn vote_audience_poll(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<AnswerBody>,
+) -> HttpResult<HttpResponse> {
+	let params = VoteAudiencePollData { lobby_id: path.into_inner(), answer: params.answer };
+	super::vote_audience_poll(&db, &session, &request, params).await
+}
+
+#[post("/lobbies/{lobby_id}/selections")]
+async fn select_question(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+	params: web::Json<QuestionBody>,
+) -> HttpResult<HttpResponse> {
+	let params = SelectQuestionData { lobby_id: path.into_inner(), question: params.question };
+	super::select_question(&db, &session, &request, params).await
+}
+
+#[post("/lobbies/{lobby_id}/buzzes")]
+async fn buzz(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	path: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	let params = BuzzData { lobby_id: path.into_inner() };
+	super::buzz(&db, &session, &request, params).await
+}
@@ -0,0 +1,35 @@
+//original routes of the player API, kept for existing clients
+
+use actix_session::Session;
+use actix_web::{get, web, HttpRequest, HttpResponse, Result as HttpResult};
+
+use super::SetNameData;
+use crate::datahandler::DataHandler;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+	cfg.service(set_name).service(get_name).service(get_question_sets);
+}
+
+#[get("/set_name")]
+async fn set_name(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Query<SetNameData>,
+) -> HttpResult<HttpResponse> {
+	super::set_name(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/get_name")]
+async fn get_name(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+) -> HttpResult<HttpResponse> {
+	super::get_name(&db, &session, &request).await
+}
+
+#[get("/get_question_sets")]
+async fn get_question_sets(db: web::Data<DataHandler>) -> HttpResult<HttpResponse> {
+	super::get_question_sets(&db).await
+}
@@ -0,0 +1,35 @@
+//resource oriented routes of the player API, taking JSON bodies
+
+use actix_session::Session;
+use actix_web::{get, put, web, HttpRequest, HttpResponse, Result as HttpResult};
+
+use super::SetNameData;
+use crate::datahandler::DataHandler;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+	cfg.service(get_name).service(set_name).service(get_question_sets);
+}
+
+#[get("/me/name")]
+async fn get_name(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+) -> HttpResult<HttpResponse> {
+	super::get_name(&db, &session, &request).await
+}
+
+#[put("/me/name")]
+async fn set_name(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	params: web::Json<SetNameData>,
+) -> HttpResult<HttpResponse> {
+	super::set_name(&db, &session, &request, params.into_inner()).await
+}
+
+#[get("/question_sets")]
+async fn get_question_sets(db: web::Data<DataHandler>) -> HttpResult<HttpResponse> {
+	super::get_question_sets(&db).await
+}
@@ -4,33 +4,70 @@ use std::{
 	time::Duration,
 };
 
+use actix_session::Session;
 use actix_web::{error, get, rt, web, HttpRequest, HttpResponse, Result as HttpResult};
 use futures::Stream;
-use tokio::sync::broadcast;
-use tokio_stream::wrappers::BroadcastStream;
+use serde::Serialize;
+use tokio_stream::{wrappers::BroadcastStream, StreamExt};
 
-use super::ensure_cookie_consent;
-use crate::{datahandler::DataHandler, game::Event};
+use super::{ensure_cookie_consent, get_lobby_password};
+use crate::datahandler::{DataHandler, LobbyListUpdate};
 
 const PING_INTERVAL: u64 = 10; //interval to ping clients in seconds
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-	cfg.service(event_stream);
+	cfg.service(lobby_list_stream).service(event_stream).service(private_event_stream);
 }
 
+// Stream of changes to the public lobby list, for all listed lobbies
+#[get("/lobbies")]
+async fn lobby_list_stream(
+	db: web::Data<DataHandler>,
+	request: HttpRequest,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(&request)?;
+
+	let receiver = db.subscribe_lobby_list().await.map_err(error::ErrorInternalServerError)?;
+	let db = db.into_inner();
+	let updates = futures::StreamExt::then(BroadcastStream::new(receiver), move |item| {
+		let db = db.clone();
+		async move {
+			let lobby_id = item?;
+			//the lobby is reported as removed if it can not be looked up
+			let lobby = db.get_lobby_list_entry(lobby_id.clone()).await.ok().flatten();
+			Ok(LobbyListUpdate { lobby_id, lobby })
+		}
+	});
+	let client = EventStreamClient::new("lobby_list_update", Box::pin(updates));
+
+	let mut resp = HttpResponse::Ok();
+	resp.append_header(("Content-Type", "text/event-stream"))
+		.append_header(("Cache-Control", "no-cache"));
+	Ok(resp.streaming(client))
+}
+
+// Stream of a lobby's events, password protected lobbies need membership or the
+// password in the Lobby-Password header
 #[get("/{lobby_id}")]
 async fn event_stream(
 	db: web::Data<DataHandler>,
+	session: Session,
 	request: HttpRequest,
 	lobby_id: web::Path<String>,
 ) -> HttpResult<HttpResponse> {
 	ensure_cookie_consent(&request)?;
 
+	let uuid = session.get::<String>("uuid")?;
 	let db_lobby =
 		db.get_lobby(lobby_id.into_inner()).await.map_err(error::ErrorInternalServerError)?;
-	if db_lobby.is_some() {
-		let lobby = db_lobby.unwrap();
-		let client = EventStreamClient::new(lobby.subsribe_events().await);
+	if let Some(lobby) = db_lobby {
+		if !lobby.may_watch(uuid.as_deref(), get_lobby_password(&request)).await {
+			return Err(error::ErrorUnauthorized("Wrong lobby password!"));
+		}
+		let client = EventStreamClient::new(
+			"game_event",
+			Box::pin(BroadcastStream::new(lobby.subsribe_events().await)),
+		);
 
 		let mut resp = HttpResponse::Ok();
 		resp.append_header(("Content-Type", "text/event-stream"))
@@ -41,15 +78,53 @@ async fn event_stream(
 	}
 }
 
-struct EventStreamClient {
-	event_source: BroadcastStream<Event>,
+// Stream of the events only addressed to the current player
+#[get("/{lobby_id}/private")]
+async fn private_event_stream(
+	db: web::Data<DataHandler>,
+	session: Session,
+	request: HttpRequest,
+	lobby_id: web::Path<String>,
+) -> HttpResult<HttpResponse> {
+	ensure_cookie_consent(&request)?;
+
+	if let Some(uuid) = session.get::<String>("uuid")? {
+		let db_lobby =
+			db.get_lobby(lobby_id.into_inner()).await.map_err(error::ErrorInternalServerError)?;
+		if let Some(lobby) = db_lobby {
+			let private_events = BroadcastStream::new(lobby.subscribe_private_events().await)
+				.filter_map(move |item| match item {
+					Ok((receiver, event)) if receiver == uuid => Some(Ok(event)),
+					Ok(_) => None,
+					Err(err) => Some(Err(err)),
+				});
+			let client = EventStreamClient::new("game_event", Box::pin(private_events));
+
+			let mut resp = HttpResponse::Ok();
+			resp.append_header(("Content-Type", "text/event-stream"))
+				.append_header(("Cache-Control", "no-cache"));
+			Ok(resp.streaming(client))
+		} else {
+			Err(error::ErrorNotFound("Lobby not found: Lobby UUID not in database!"))
+		}
+	} else {
+		Err(error::ErrorUnauthorized("Invalid session: No player UUID!"))
+	}
+}
+
+type EventSource<T> = Pin<Box<dyn Stream<Item = Result<T, BroadcastStreamRecvError>>>>;
+
+struct EventStreamClient<T> {
+	event_name: &'static str,
+	event_source: EventSource<T>,
 	pinger: rt::time::Interval,
 }
 
-impl EventStreamClient {
-	pub fn new(event_source: broadcast::Receiver<Event>) -> Self {
+impl<T: Serialize> EventStreamClient<T> {
+	pub fn new(event_name: &'static str, event_source: EventSource<T>) -> Self {
 		EventStreamClient {
-			event_source: BroadcastStream::new(event_source),
+			event_name,
+			event_source,
 			pinger: rt::time::interval(Duration::from_secs(PING_INTERVAL)),
 		}
 	}
@@ -58,14 +133,14 @@ impl EventStreamClient {
 		web::Bytes::from("event: ping\ndata: \"ping\"\n\n")
 	}
 
-	fn event_to_bytes(event: Event) -> web::Bytes {
+	fn event_to_bytes(&self, event: T) -> web::Bytes {
 		let data = serde_json::to_string(&event).unwrap();
-		web::Bytes::from(format!("event: game_event\ndata: {}\n\n", data))
+		web::Bytes::from(format!("event: {}\ndata: {}\n\n", self.event_name, data))
 	}
 }
 
 use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
-impl Stream for EventStreamClient {
+impl<T: Serialize> Stream for EventStreamClient<T> {
 	type Item = Result<web::Bytes, error::Error>;
 
 	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
@@ -74,11 +149,11 @@ impl Stream for EventStreamClient {
 		if self.pinger.poll_tick(cx).is_ready()
 		//will register wakeup through cx.waker() on pending
 		{
-			Poll::Ready(Some(Ok(EventStreamClient::ping())))
+			Poll::Ready(Some(Ok(Self::ping())))
 		} else {
-			match Pin::new(&mut self.event_source).poll_next(cx) //will register wakeup through cx.waker() on pending
+			match self.event_source.as_mut().poll_next(cx) //will register wakeup through cx.waker() on pending
             {
-                Poll::Ready(Some(Ok(content))) => Poll::Ready(Some(Ok(EventStreamClient::event_to_bytes(content)))),
+                Poll::Ready(Some(Ok(content))) => Poll::Ready(Some(Ok(self.event_to_bytes(content)))),
                 Poll::Ready(None) => Poll::Ready(None), //event sender connection was closed
                 Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(_)))) => Poll::Ready(None), //close connection when messages were lost
                 Poll::Pending => Poll::Pending,
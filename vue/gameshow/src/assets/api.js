@@ -61,9 +61,11 @@ export default {
         }
     },
     //join an existing lobby
-    join_lobby: async function(uuid)
+    join_lobby: async function(uuid, language)
     {
-        let response = await fetch(apiPath + "join_lobby?uuid=" + encodeURIComponent(uuid));
+        let url = apiPath + "join_lobby?uuid=" + encodeURIComponent(uuid);
+        if (language) url += "&language=" + encodeURIComponent(language);
+        let response = await fetch(url);
         if (!response.ok) {
             let body = await response.text();
             if (response.status == 404) return { valid: false, not_found: true, closed: false, msg: body };
@@ -173,10 +175,10 @@ export default {
         }
     },
     //kick a player
-    kick_player: async function (lobby_id, name) {
+    kick_player: async function (lobby_id, player_id) {
         const params = {
             lobby_id: lobby_id,
-            name: name,
+            player_id: player_id,
         };
         const request = {
             method: "POST",
@@ -194,11 +196,11 @@ export default {
         }
     },
     //change a player's attributes
-    set_player_attributes: async function(lobby_id, name, money, jokers)
+    set_player_attributes: async function(lobby_id, player_id, money, jokers)
     {
         const params = {
             lobby_id: lobby_id,
-            name: name,
+            player_id: player_id,
             money: money,
             jokers: jokers,
         };